@@ -14,6 +14,7 @@ import DeleteIcon from "@mui/icons-material/Delete";
 import IconButton from "@mui/material/IconButton";
 
 import { QueryComponentColor } from "@/model/QueryComponentColor";
+import { operatorTakesValue } from "@/helper/StringHelper";
 
 export default function SelectComponent({
   queryParam,
@@ -27,6 +28,9 @@ export default function SelectComponent({
   const [colorHandler, _updateColorHandler] =
     useState<QueryComponentColor>(componentColor);
   const [subqueries, setSubqueries] = useState<QueryModel[] | undefined>();
+  const [selectedOperator, setSelectedOperator] = useState<string>("");
+  // operators offered depend on the data type of the selected attribute
+  const [typedOperators, setTypedOperators] = useState<TypedOperators>();
 
   const thisTextAccentColor = colorHandler.getTextColor();
   const thisAccentColor = colorHandler.getAccentColor();
@@ -38,6 +42,17 @@ export default function SelectComponent({
 
   function handleSelectedAttribute(value: string) {
     query.selectedAttribute = value;
+
+    const attribute = schemaInfo.attributes.find((attr) => attr.name == value);
+    const operators = attribute
+      ? schemaInfo.typed_operators[attribute.data_type]
+      : undefined;
+    setTypedOperators(operators);
+
+    if (!operators?.operators.includes(selectedOperator)) {
+      query.selectedOperator = undefined;
+      setSelectedOperator("");
+    }
   }
 
   function handleSelectedInput(inputText: string) {
@@ -46,6 +61,19 @@ export default function SelectComponent({
 
   function handleSelectedOperator(event: SelectChangeEvent) {
     query.selectedOperator = event.target.value;
+    setSelectedOperator(event.target.value);
+  }
+
+  function valueLabel(): string {
+    switch (selectedOperator) {
+      case "In":
+      case "NotIn":
+        return "Digite os valores separados por vírgula";
+      case "Between":
+        return "Digite o início e o fim separados por vírgula";
+      default:
+        return "Digite um valor ou campo";
+    }
   }
 
   function addSubqueries() {
@@ -107,10 +135,11 @@ export default function SelectComponent({
             <InputLabel id="label-operador">Operador</InputLabel>
             <Select
               onChange={handleSelectedOperator}
+              value={selectedOperator}
               className="mui-select"
               labelId="label-operador"
             >
-              {schemaInfo.operators.map((op) => (
+              {(typedOperators?.operators ?? []).map((op) => (
                 <MenuItem key={op} value={op}>
                   {op}
                 </MenuItem>
@@ -118,26 +147,28 @@ export default function SelectComponent({
             </Select>
           </FormControl>
 
-          <Autocomplete
-            freeSolo
-            className="select-attr"
-            popupIcon={""}
-            clearIcon={""}
-            options={schemaInfo.attributes.map((attr) => attr.name)}
-            onInputChange={(event: any, newValue: string | null) => {
-              handleSelectedInput(newValue ?? "");
-            }}
-            renderInput={(params) => (
-              <TextField {...params} label="Digite um valor ou campo" />
-            )}
-            renderOption={(props, option) => {
-              return (
-                <li {...props} key={option}>
-                  {option}
-                </li>
-              );
-            }}
-          />
+          {operatorTakesValue(selectedOperator) && (
+            <Autocomplete
+              freeSolo
+              className="select-attr"
+              popupIcon={""}
+              clearIcon={""}
+              options={schemaInfo.attributes.map((attr) => attr.name)}
+              onInputChange={(event: any, newValue: string | null) => {
+                handleSelectedInput(newValue ?? "");
+              }}
+              renderInput={(params) => (
+                <TextField {...params} label={valueLabel()} />
+              )}
+              renderOption={(props, option) => {
+                return (
+                  <li {...props} key={option}>
+                    {option}
+                  </li>
+                );
+              }}
+            />
+          )}
 
           <IconButton
             aria-label="delete"
@@ -23,8 +23,8 @@ export default function Home() {
       value.attributes = value.attributes.map((attr) => {
         return {
           name: convertPathToReadableString(attr.name),
-          subset: attr.subset,
-          type: attr.type
+          subset_id: attr.subset_id,
+          data_type: attr.data_type
         } as Attribute;
       })
       setSchemaInfo(value)
@@ -17,9 +17,9 @@ function getSelectedAttributesFromQuery(
   return {
     selectedAttribute: query.selectedAttribute!,
     selectedOperator: query.selectedOperator!,
-    selectedValue: isAttribute(query.selectedInput!, schema)
+    selectedValue: isAttribute(query.selectedInput ?? "", schema)
       ? convertReadableStringToPath(query.selectedInput!)
-      : query.selectedInput!,
+      : query.selectedInput ?? "",
     selectedLogical: isLast ? "" : query.selectedLogical!,
     selectedLogicalSubquerie: query.selectedLogicalSubquerie ?? "",
     subqueries:
@@ -1,3 +1,5 @@
+import { operatorTakesValue } from "./StringHelper";
+
 export function validateProjection(projection: string[]): boolean {
   return projection.length > 0;
 }
@@ -18,7 +20,10 @@ function validateQuery(query: QueryModel): boolean {
   if (query.selectedOperator == undefined || query.selectedOperator == "")
     return false;
 
-  if (query.selectedInput == undefined || query.selectedAttribute == "")
+  if (
+    operatorTakesValue(query.selectedOperator) &&
+    (query.selectedInput == undefined || query.selectedInput == "")
+  )
     return false;
 
   if (
@@ -22,7 +22,7 @@ export function generateStringFromQueryArray(
   query.forEach((q) => {
     base += `${convertReadableStringToPath(q.selectedAttribute)} ${
       translateOperator(q.selectedOperator)
-    } ${q.selectedValue} ${
+    } ${generateValueString(q.selectedOperator, q.selectedValue)} ${
       q.subqueries != undefined && q.subqueries.length > 0
         ? q.selectedLogicalSubquerie
         : ""
@@ -33,6 +33,39 @@ export function generateStringFromQueryArray(
   return base.replace(/  +/g, " ").trim();
 }
 
+// Values of list and range operators are typed separated by commas and sent as a
+// parenthesized list of literals or as the bounds of the range, null checks take none.
+function generateValueString(operator: string, value: string | number): string {
+  switch (operator) {
+    case "In":
+    case "NotIn":
+      return `(${splitValues(value).map(quoteLiteral).join(", ")})`;
+    case "Between":
+      return splitValues(value).map(quoteLiteral).join(" AND ");
+    case "IsNull":
+    case "IsNotNull":
+      return "";
+    default:
+      return `${value}`;
+  }
+}
+
+export function operatorTakesValue(operator: string): boolean {
+  return operator != "IsNull" && operator != "IsNotNull";
+}
+
+function splitValues(value: string | number): string[] {
+  return `${value}`
+    .split(",")
+    .map((v) => v.trim())
+    .filter((v) => v != "");
+}
+
+// quoted literals are taken verbatim, so values may hold commas, parenthesis or AND
+function quoteLiteral(value: string): string {
+  return `"${value.replaceAll("\\", "\\\\").replaceAll('"', '\\"')}"`;
+}
+
 function translateOperator(operator: string): string{
   let back_operator;
 
@@ -55,6 +88,51 @@ function translateOperator(operator: string): string{
   case "NotEqualTo":
     back_operator = "ne";
     break;
+  case "Contains":
+    back_operator = "contains";
+    break;
+  case "StartsWith":
+    back_operator = "starts_with";
+    break;
+  case "EndsWith":
+    back_operator = "ends_with";
+    break;
+  case "Like":
+    back_operator = "like";
+    break;
+  case "EqualToIgnoreCase":
+    back_operator = "ieq";
+    break;
+  case "NotEqualToIgnoreCase":
+    back_operator = "ine";
+    break;
+  case "ContainsIgnoreCase":
+    back_operator = "icontains";
+    break;
+  case "StartsWithIgnoreCase":
+    back_operator = "istarts_with";
+    break;
+  case "EndsWithIgnoreCase":
+    back_operator = "iends_with";
+    break;
+  case "LikeIgnoreCase":
+    back_operator = "ilike";
+    break;
+  case "In":
+    back_operator = "in";
+    break;
+  case "NotIn":
+    back_operator = "not_in";
+    break;
+  case "Between":
+    back_operator = "between";
+    break;
+  case "IsNull":
+    back_operator = "is_null";
+    break;
+  case "IsNotNull":
+    back_operator = "is_not_null";
+    break;
   default:
     back_operator = "UNKOWN";
   }
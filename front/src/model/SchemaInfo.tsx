@@ -2,11 +2,17 @@ type SchemaInfo = {
   attributes: Attribute[];
   subsets: number[][];
   operators: string[];
+  typed_operators: Record<string, TypedOperators>;
   logical_operators: string[];
 };
 
+type TypedOperators = {
+  operators: string[];
+  input: "number" | "text" | "date";
+};
+
 type Attribute = {
   name: string;
-  type: string;
-  subset: number;
+  data_type: string;
+  subset_id: number;
 };
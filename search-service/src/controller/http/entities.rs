@@ -5,12 +5,16 @@ use axum::{
 };
 use serde::{Deserialize, Serialize};
 
-use crate::manager::ManagerError;
+use crate::manager::{ManagerError, SearchOptions};
+use crate::query_representation::initial::Filters;
 
 #[derive(Debug, Deserialize)]
 pub struct SearchRequest {
     pub projection: Vec<String>,
-    pub filters: String,
+    pub filters: Filters,
+    // every other field of the request, all of them optional
+    #[serde(flatten)]
+    pub options: SearchOptions,
 }
 
 #[derive(Debug)]
@@ -59,6 +63,9 @@ pub struct RequestError {
     #[serde(skip_serializing)]
     pub status_code: StatusCode,
     pub message: String,
+    // structured description of what was wrong with the request, when available
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<serde_json::Value>,
 }
 
 impl IntoResponse for RequestError {
@@ -69,15 +76,43 @@ impl IntoResponse for RequestError {
 
 impl From<ManagerError> for RequestError {
     fn from(error: ManagerError) -> Self {
-        let (status_code, message) = match error {
-            ManagerError::ParseError(e) => (StatusCode::BAD_REQUEST, e),
-            ManagerError::QueryBuildError(e) => (StatusCode::INTERNAL_SERVER_ERROR, e),
-            ManagerError::Unknown(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
+        let (status_code, message, details) = match error {
+            ManagerError::ParseError(e) => (
+                StatusCode::BAD_REQUEST,
+                e.to_string(),
+                serde_json::to_value(&e).ok(),
+            ),
+            ManagerError::QueryBuildError(e) => (StatusCode::INTERNAL_SERVER_ERROR, e, None),
+            ManagerError::TypeErrors(ref errors) => (
+                StatusCode::BAD_REQUEST,
+                error.to_string(),
+                Some(serde_json::json!({ "type_errors": errors })),
+            ),
+            ManagerError::UnknownAttributes(ref attributes) => (
+                StatusCode::BAD_REQUEST,
+                error.to_string(),
+                Some(serde_json::json!({ "unknown_attributes": attributes })),
+            ),
+            ManagerError::NormalizationError(e) => (StatusCode::BAD_REQUEST, e, None),
+            ManagerError::UnknownTables(ref tables) => (
+                StatusCode::BAD_REQUEST,
+                error.to_string(),
+                Some(serde_json::json!({ "unknown_tables": tables })),
+            ),
+            ManagerError::InvalidPagination(_)
+            | ManagerError::InvalidAggregation(_)
+            | ManagerError::InvalidDistinct(_)
+            | ManagerError::InvalidProjection(_)
+            | ManagerError::UnsupportedSearch(_) => {
+                (StatusCode::BAD_REQUEST, error.to_string(), None)
+            }
+            ManagerError::Unknown(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string(), None),
         };
 
         Self {
             status_code,
             message,
+            details,
         }
     }
 }
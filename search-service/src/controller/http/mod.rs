@@ -66,6 +66,7 @@ async fn get_filter_properties(
         .map_err(|e| RequestError {
             status_code: StatusCode::INTERNAL_SERVER_ERROR,
             message: e.to_string(),
+            details: None,
         })?;
 
     let res = serde_json::json!({
@@ -82,12 +83,16 @@ async fn search(
     let SearchRequest {
         projection,
         filters,
+        options,
     } = payload;
 
-    let res = manager.search(projection, filters).await?;
+    let res = manager
+        .search_with_options(projection, filters, options)
+        .await?;
     let res = serde_json::to_string(&res).map_err(|_| RequestError {
         status_code: StatusCode::INTERNAL_SERVER_ERROR,
         message: "could not serialize response".into(),
+        details: None,
     })?;
     Ok(Response::new(StatusCode::OK, res))
 }
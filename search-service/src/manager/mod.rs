@@ -1,12 +1,34 @@
+pub mod pagination;
 pub mod properties;
 
 use std::sync::Arc;
 
+use serde::Deserialize;
 use thiserror::Error;
 
 use crate::{
-    manager::properties::{Properties, PropertiesManager},
-    query_representation::initial::initial_to_command,
+    manager::{
+        pagination::{
+            get_page, get_sort_keys, keyset_command, take_cursor_values, uses_cursor, Cursor,
+            Paging,
+        },
+        properties::{Properties, PropertiesManager},
+    },
+    query_representation::{
+        initial::{errors::ParseError, filter_tree::FilterNode, filters_to_command, Filters},
+        intermediary::{
+            composite_command::{CompositeCommand, LogicalOperator},
+            get_command_attributes,
+            normalization::{normalize_command, NormalForm},
+            single_command::DataType,
+            typing::{errors::TypeError, resolve_command_types, AttributeTypes},
+            Command,
+        },
+        ultimate::{
+            get_result_tables, Aggregate, AggregateFunction, Deduplication, OrderBy, Page,
+            Query, QueryOptions,
+        },
+    },
     relational::{
         entities::DbSchema,
         table_search::{entities::TableSearchInfo, TableSearch},
@@ -21,10 +43,70 @@ pub enum ManagerError {
     Unknown(#[from] anyhow::Error),
 
     #[error("Failed to parse initial query representation: {0}")]
-    ParseError(String),
+    ParseError(ParseError),
 
     #[error("Failed to build query: {0}")]
     QueryBuildError(String),
+
+    #[error("Invalid filter: {}", .0.iter().map(|e| e.to_string()).collect::<Vec<String>>().join("; "))]
+    TypeErrors(Vec<TypeError>),
+
+    #[error("Unknown attributes: {}", .0.join(", "))]
+    UnknownAttributes(Vec<String>),
+
+    #[error("Failed to normalize filters: {0}")]
+    NormalizationError(String),
+
+    #[error("Unknown tables: {}", .0.join(", "))]
+    UnknownTables(Vec<String>),
+
+    #[error("Invalid pagination: {0}")]
+    InvalidPagination(String),
+
+    #[error("Invalid aggregation: {0}")]
+    InvalidAggregation(String),
+
+    #[error("Invalid distinct search: {0}")]
+    InvalidDistinct(String),
+
+    #[error("Invalid projection: {0}")]
+    InvalidProjection(String),
+
+    #[error("Unsupported search: {0}")]
+    UnsupportedSearch(String),
+}
+
+// Optional behaviour of a search, read from the search request next to its projection
+// and filters. Plain searches use the defaults.
+#[derive(Default, Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct SearchOptions {
+    // normal form the filters are rewritten to, they are only simplified when missing
+    pub normal_form: Option<NormalForm>,
+    // tables (schema.table) whose rows may be missing from the results, joined with LEFT JOIN
+    pub optional_tables: Vec<String>,
+    // attributes the results are sorted by, in order of precedence
+    pub order_by: Vec<OrderBy>,
+    // most rows returned, up to MAX_PAGE_SIZE
+    pub page_size: Option<u64>,
+    // page returned, starting at 1, the first one when missing
+    pub page: Option<u64>,
+    // rows skipped before the first one returned, instead of a page
+    pub offset: Option<u64>,
+    // whether pages are chosen by number and offset or by cursor
+    pub paging: Paging,
+    // next_cursor of the previous page, to get the page after it
+    pub cursor: Option<String>,
+    // aggregates returned for each group, e.g. the count of movies per genre
+    pub aggregates: Vec<Aggregate>,
+    // attributes the results are grouped by, the projection may only use them
+    pub group_by: Vec<String>,
+    // filters of the groups, e.g. "movies gt 50" for an aggregate aliased movies
+    pub having: Option<Filters>,
+    // how rows repeated by joined tables are avoided: semi_join (default), distinct or none
+    pub deduplication: Deduplication,
+    // case insensitive operators also ignore accents, when /properties tells ignore_accents
+    pub ignore_accents: bool,
 }
 
 #[derive(Clone)]
@@ -53,38 +135,225 @@ impl SearchServiceManager {
     pub async fn search(
         &self,
         projection: Vec<String>,
-        filters: String,
+        filters: Filters,
     ) -> Result<serde_json::Value, ManagerError> {
-        let projection = match self.storage.get_database() {
-            "postgres" => projection
-                .iter()
-                .map(|att| format!("{}::TEXT", att))
-                .collect(),
-            "mysql" => projection,
-            _ => projection,
+        self.search_with_options(projection, filters, SearchOptions::default())
+            .await
+    }
+
+    pub async fn search_with_options(
+        &self,
+        projection: Vec<String>,
+        filters: Filters,
+        options: SearchOptions,
+    ) -> Result<serde_json::Value, ManagerError> {
+        let mut command =
+            filters_to_command(filters).map_err(ManagerError::ParseError)?;
+
+        let db_schema = self.storage.get_db_schema_info().await?;
+
+        let attribute_types = self.get_attribute_types(&db_schema)?;
+        resolve_command_types(&mut command, &attribute_types)
+            .map_err(ManagerError::TypeErrors)?;
+
+        let aggregate_names: Vec<String> = options.aggregates.iter().map(|a| a.name()).collect();
+
+        let requested_attributes: Vec<String> = projection
+            .iter()
+            .cloned()
+            .chain(
+                options
+                    .order_by
+                    .iter()
+                    .map(|o| o.attribute.to_owned())
+                    .filter(|attribute| !aggregate_names.contains(attribute)),
+            )
+            .chain(options.group_by.iter().cloned())
+            .chain(options.aggregates.iter().filter_map(|a| a.attribute.to_owned()))
+            .collect();
+
+        validate_projection(&projection, &options)?;
+        validate_attributes(&requested_attributes, &command, &attribute_types)?;
+        validate_tables(&options.optional_tables, &db_schema)?;
+        validate_aggregation(&projection, &options, &attribute_types)?;
+        validate_distinct(&projection, &options)?;
+
+        if options.ignore_accents && !self.storage.supports_unaccent() {
+            return Err(ManagerError::UnsupportedSearch(
+                "the database can't ignore accents".into(),
+            ));
+        }
+
+        let command = normalize_command(command, options.normal_form.as_ref())
+            .map_err(|e| ManagerError::NormalizationError(e.to_string()))?;
+
+        let having = options
+            .having
+            .clone()
+            .map(|having| get_having_command(having, &options, &attribute_types))
+            .transpose()?;
+
+        let page = get_page(&options)?;
+
+        let table_search = self.get_table_search(&db_schema).await?;
+
+        // shape shared by the count and the page, the count joins the same tables
+        // as the page and only leaves out its order and limit
+        let base_options = QueryOptions {
+            optional_tables: options.optional_tables.clone(),
+            order_by: options.order_by.clone(),
+            aggregates: options.aggregates.clone(),
+            group_by: options.group_by.clone(),
+            having: having.clone(),
+            distinct: options.deduplication == Deduplication::Distinct,
+            semi_joins: options.deduplication == Deduplication::SemiJoin,
+            ignore_accents: options.ignore_accents,
+            ..Default::default()
         };
 
-        let command =
-            initial_to_command(filters).map_err(|e| ManagerError::ParseError(e.to_string()))?;
+        let count_options = QueryOptions {
+            count: true,
+            ..base_options.clone()
+        };
+        let count_query = self.build_query(&command, projection.clone(), &table_search, count_options)?;
+        let count = self.storage.execute(count_query).await?;
 
-        let table_search = self
-            .get_table_search(&self.storage.get_db_schema_info().await?)
-            .await?;
+        let total_count = count
+            .first()
+            .and_then(|row| row["total_count"].as_str())
+            .and_then(|total_count| total_count.parse::<u64>().ok())
+            .ok_or_else(|| ManagerError::QueryBuildError("Failed to count the results".into()))?;
 
-        let visitor = DatabaseVisitor::new(table_search);
+        if !uses_cursor(&options) {
+            let query_options = QueryOptions {
+                page: Some(page.clone()),
+                ..base_options
+            };
 
-        let query = command
-            .accept(projection, Arc::new(visitor))
+            let query = self.build_query(&command, projection, &table_search, query_options)?;
+            let res = self.storage.execute(query).await?;
+
+            let has_more = page.offset + (res.len() as u64) < total_count;
+
+            let mut res = serde_json::json!({
+                "search_result": serde_json::json!(res),
+                "filters": FilterNode::from(&command),
+                "pagination": {
+                    "page_size": page.limit,
+                    "offset": page.offset,
+                    "total_count": total_count,
+                    "has_more": has_more,
+                },
+            });
+            if let Some(having) = &having {
+                res["having"] = serde_json::json!(FilterNode::from(having));
+            }
+            return Ok(res);
+        }
+
+        // rows are told apart by the keys of the tables they are made of
+        let result_tables = get_result_tables(&projection, &command, &table_search, &base_options)
             .map_err(|e| ManagerError::QueryBuildError(e.to_string()))?;
+        let sort_keys = get_sort_keys(
+            &options.order_by,
+            &result_tables,
+            &db_schema,
+            &attribute_types,
+            self.storage.get_dialect().as_ref(),
+        )?;
+
+        // the page starts after the cursor, on top of the filters of the search
+        let page_command = match &options.cursor {
+            Some(cursor) => {
+                let keyset = keyset_command(&Cursor::decode(cursor)?, &sort_keys, &attribute_types)?;
+                Command::CompositeCommand(CompositeCommand::new(
+                    LogicalOperator::And,
+                    vec![command.clone(), keyset],
+                ))
+            }
+            None => command.clone(),
+        };
+
+        let cursor_attributes = sort_keys.iter().map(|k| k.attribute.to_owned()).collect();
+
+        // one more row than the page tells whether there are more
+        let query_options = QueryOptions {
+            order_by: sort_keys.clone(),
+            page: Some(Page {
+                limit: page.limit + 1,
+                offset: 0,
+            }),
+            cursor_attributes,
+            ..base_options
+        };
 
-        let res = self.storage.execute(query).await?;
+        let query = self.build_query(&page_command, projection, &table_search, query_options)?;
+        let mut res = self.storage.execute(query).await?;
+
+        let has_more = res.len() as u64 > page.limit;
+        res.truncate(page.limit as usize);
+
+        let mut last_values = Vec::new();
+        for row in res.iter_mut() {
+            last_values = take_cursor_values(row, sort_keys.len());
+        }
+
+        let next_cursor = if has_more {
+            Some(Cursor::new(&sort_keys, last_values).encode()?)
+        } else {
+            None
+        };
 
         let res = serde_json::json!({
             "search_result": serde_json::json!(res),
+            "filters": FilterNode::from(&command),
+            "pagination": {
+                "page_size": page.limit,
+                "total_count": total_count,
+                "has_more": has_more,
+                "next_cursor": next_cursor,
+            },
         });
         Ok(res)
     }
 
+    fn build_query(
+        &self,
+        command: &Command,
+        projection: Vec<String>,
+        table_search: &TableSearch,
+        query_options: QueryOptions,
+    ) -> Result<Query, ManagerError> {
+        let visitor = DatabaseVisitor::new(
+            table_search.clone(),
+            self.storage.get_dialect(),
+            query_options,
+        );
+
+        command
+            .accept(projection, Arc::new(visitor))
+            .map_err(|e| ManagerError::QueryBuildError(e.to_string()))
+    }
+
+    // Attributes of native types without a data type can't be compared, they are left out
+    // as if they weren't in the schema.
+    fn get_attribute_types(&self, db_schema: &DbSchema) -> Result<AttributeTypes, ManagerError> {
+        let mut attribute_types = AttributeTypes::new();
+
+        for table in db_schema.tables.iter() {
+            for attribute in table.attributes.iter() {
+                let full_attr_name = format!("{}.{}.{}", &table.schema, &table.name, &attribute.name);
+                let Ok(data_type) = self.storage.translate_native_type(&attribute.data_type) else {
+                    continue;
+                };
+
+                attribute_types.insert(full_attr_name, data_type);
+            }
+        }
+
+        Ok(attribute_types)
+    }
+
     async fn get_table_search(&self, db_schema: &DbSchema) -> Result<TableSearch, ManagerError> {
         let tables_search_info: Vec<TableSearchInfo> = db_schema
             .tables
@@ -101,6 +370,224 @@ impl SearchServiceManager {
 
 
 
+// Only attributes of the introspected schema, which is limited to the allowed
+// schemas, may reach the query. Attributes compared as values are checked too.
+fn validate_attributes(
+    attributes: &[String],
+    command: &Command,
+    attribute_types: &AttributeTypes,
+) -> Result<(), ManagerError> {
+    let mut unknown_attributes: Vec<String> = Vec::new();
+
+    for attribute in attributes.iter().chain(get_command_attributes(command).iter()) {
+        if !attribute_types.contains_key(attribute) && !unknown_attributes.contains(attribute) {
+            unknown_attributes.push(attribute.to_owned());
+        }
+    }
+
+    if !unknown_attributes.is_empty() {
+        return Err(ManagerError::UnknownAttributes(unknown_attributes));
+    }
+
+    Ok(())
+}
+
+// Aggregated searches return one row for each group, so the projection and the order
+// may only use the attributes grouped by and the aggregates, which are named by their alias.
+fn validate_aggregation(
+    projection: &[String],
+    options: &SearchOptions,
+    attribute_types: &AttributeTypes,
+) -> Result<(), ManagerError> {
+    if options.aggregates.is_empty() && options.group_by.is_empty() {
+        return Ok(());
+    }
+
+    let mut aggregate_names: Vec<String> = Vec::new();
+
+    for aggregate in options.aggregates.iter() {
+        let name = aggregate.name();
+
+        let is_identifier = name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !is_identifier {
+            return Err(ManagerError::InvalidAggregation(format!(
+                "{} is not a valid alias",
+                name
+            )));
+        }
+        // results are keyed by column name, grouped attributes by their own
+        let grouped_names = options.group_by.iter().map(|g| g.rsplit('.').next().unwrap_or(g));
+        if aggregate_names.contains(&name) || grouped_names.clone().any(|g| g == name) {
+            return Err(ManagerError::InvalidAggregation(format!(
+                "{} names more than one column",
+                name
+            )));
+        }
+        aggregate_names.push(name);
+
+        let data_type = aggregate
+            .attribute
+            .as_ref()
+            .and_then(|attribute| attribute_types.get(attribute).map(|t| (attribute, t)));
+
+        match (&aggregate.function, data_type) {
+            (AggregateFunction::Count | AggregateFunction::CountDistinct, _) => {}
+            (function, None) => {
+                return Err(ManagerError::InvalidAggregation(format!(
+                    "{} needs an attribute",
+                    function
+                )))
+            }
+            (function, Some((attribute, data_type))) => {
+                let accepted = match function {
+                    AggregateFunction::Sum | AggregateFunction::Avg => {
+                        matches!(data_type, DataType::Integer | DataType::Float)
+                    }
+                    _ => data_type.is_ordered() || *data_type == DataType::String,
+                };
+                if !accepted {
+                    return Err(ManagerError::InvalidAggregation(format!(
+                        "{} can't be applied to {}, a {} attribute",
+                        function, attribute, data_type
+                    )));
+                }
+            }
+        }
+    }
+
+    if let Some(attribute) = projection.iter().find(|p| !options.group_by.contains(p)) {
+        return Err(ManagerError::InvalidAggregation(format!(
+            "{} must be grouped by to be projected",
+            attribute
+        )));
+    }
+
+    if let Some(order) = options
+        .order_by
+        .iter()
+        .find(|o| !options.group_by.contains(&o.attribute) && !aggregate_names.contains(&o.attribute))
+    {
+        return Err(ManagerError::InvalidAggregation(format!(
+            "{} must be grouped by or aggregated to sort by it",
+            order.attribute
+        )));
+    }
+
+    Ok(())
+}
+
+// Filters of the groups may only use the attributes grouped by and the aggregates,
+// typed after the values they produce. They are checked and simplified like the filters.
+fn get_having_command(
+    having: Filters,
+    options: &SearchOptions,
+    attribute_types: &AttributeTypes,
+) -> Result<Command, ManagerError> {
+    if options.aggregates.is_empty() && options.group_by.is_empty() {
+        return Err(ManagerError::InvalidAggregation(
+            "having filters groups, it needs aggregates or group_by".into(),
+        ));
+    }
+
+    let mut command = filters_to_command(having).map_err(ManagerError::ParseError)?;
+
+    let mut group_types = AttributeTypes::new();
+    for attribute in options.group_by.iter() {
+        if let Some(data_type) = attribute_types.get(attribute) {
+            group_types.insert(attribute.to_owned(), data_type.clone());
+        }
+    }
+    for aggregate in options.aggregates.iter() {
+        if let Some(data_type) = get_aggregate_type(aggregate, attribute_types) {
+            group_types.insert(aggregate.name(), data_type);
+        }
+    }
+
+    if let Some(attribute) = get_command_attributes(&command)
+        .into_iter()
+        .find(|attribute| !group_types.contains_key(attribute))
+    {
+        return Err(ManagerError::InvalidAggregation(format!(
+            "{} must be grouped by or aggregated to filter groups by it",
+            attribute
+        )));
+    }
+
+    resolve_command_types(&mut command, &group_types).map_err(ManagerError::TypeErrors)?;
+
+    normalize_command(command, options.normal_form.as_ref())
+        .map_err(|e| ManagerError::NormalizationError(e.to_string()))
+}
+
+fn get_aggregate_type(aggregate: &Aggregate, attribute_types: &AttributeTypes) -> Option<DataType> {
+    let attribute_type = aggregate
+        .attribute
+        .as_ref()
+        .and_then(|attribute| attribute_types.get(attribute));
+
+    match aggregate.function {
+        AggregateFunction::Count | AggregateFunction::CountDistinct => Some(DataType::Integer),
+        AggregateFunction::Avg => Some(DataType::Float),
+        AggregateFunction::Sum | AggregateFunction::Min | AggregateFunction::Max => {
+            attribute_type.cloned()
+        }
+    }
+}
+
+// Distinct rows stand for their projection, so they may only be sorted by it. Groups
+// already are distinct, and their aggregates count every joined row.
+fn validate_distinct(projection: &[String], options: &SearchOptions) -> Result<(), ManagerError> {
+    if options.deduplication != Deduplication::Distinct {
+        return Ok(());
+    }
+
+    if !options.aggregates.is_empty() || !options.group_by.is_empty() {
+        return Err(ManagerError::InvalidDistinct(
+            "aggregated searches can't be distinct".into(),
+        ));
+    }
+
+    if let Some(order) = options.order_by.iter().find(|o| !projection.contains(&o.attribute)) {
+        return Err(ManagerError::InvalidDistinct(format!(
+            "{} must be projected to sort by it",
+            order.attribute
+        )));
+    }
+
+    Ok(())
+}
+
+// Every row needs a column, an attribute of the projection or an aggregate.
+fn validate_projection(projection: &[String], options: &SearchOptions) -> Result<(), ManagerError> {
+    if projection.is_empty() && options.aggregates.is_empty() {
+        return Err(ManagerError::InvalidProjection(
+            "the search returns no attributes nor aggregates".into(),
+        ));
+    }
+
+    Ok(())
+}
+
+fn validate_tables(tables: &[String], db_schema: &DbSchema) -> Result<(), ManagerError> {
+    let unknown_tables: Vec<String> = tables
+        .iter()
+        .filter(|table| {
+            !db_schema
+                .tables
+                .iter()
+                .any(|t| format!("{}.{}", t.schema, t.name) == **table)
+        })
+        .cloned()
+        .collect();
+
+    if !unknown_tables.is_empty() {
+        return Err(ManagerError::UnknownTables(unknown_tables));
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -110,6 +597,10 @@ mod tests {
 
     use serde_json::json;
 
+    use crate::query_representation::ultimate::{Aggregate, AggregateFunction, Deduplication, Direction, NullsOrder};
+
+    use crate::manager::pagination::MAX_PAGE_SIZE;
+
     use crate::storage::postgres::{PostgresConfig, PostgresStorage};
 
     use crate::storage::mysql::{MySQLConfig, MySQLStorage};
@@ -149,11 +640,11 @@ mod tests {
         let db_storage = aux_get_pg_storage().await?;
 
         let projection = vec![
-            "movies.person.person_name::TEXT".to_string(),
-            "movies.movie_cast.character_name::TEXT".to_string(),
-            "movies.movie.title::TEXT".to_string()
+            "movies.person.person_name".to_string(),
+            "movies.movie_cast.character_name".to_string(),
+            "movies.movie.title".to_string()
         ];
-        let filters = "movies.movie_cast.character_name eq Harry Potter".to_string();
+        let filters = Filters::Expression("movies.movie_cast.character_name eq Harry Potter".to_string());
 
         let search_manager = SearchServiceManager::new(db_storage).await;
 
@@ -164,6 +655,808 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_search_service_pg_with_filter_tree() -> Result<(), Error> {
+        let db_storage = aux_get_pg_storage().await?;
+
+        let projection = vec!["movies.movie.title".to_string()];
+        let filters: Filters = serde_json::from_value(json!({"and": [
+            {"comparison": {
+                "attribute": "movies.person.person_name",
+                "operator": "EqualTo",
+                "value": "Wagner Moura",
+                "data_type": "String"
+            }},
+            {"comparison": {
+                "attribute": "movies.movie.runtime",
+                "operator": "GreaterThan",
+                "value": 0,
+                "data_type": "Integer"
+            }}
+        ]}))?;
+
+        let search_manager = SearchServiceManager::new(db_storage).await;
+
+        let search_result = search_manager.search(projection,filters).await?;
+
+        assert_ne!(search_result["search_result"],json!([]));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_search_service_pg_with_pattern_operator() -> Result<(), Error> {
+        let db_storage = aux_get_pg_storage().await?;
+
+        let projection = vec!["movies.movie.title".to_string()];
+        let filters = Filters::Expression("movies.movie.title starts_with Harry Potter".to_string());
+
+        let search_manager = SearchServiceManager::new(db_storage).await;
+
+        let search_result = search_manager.search(projection,filters).await?;
+
+        let titles = search_result["search_result"].as_array().unwrap();
+        assert!(!titles.is_empty());
+        assert!(titles
+            .iter()
+            .all(|row| row["title"].as_str().unwrap().starts_with("Harry Potter")));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_search_service_pg_case_insensitive() -> Result<(), Error> {
+        let db_storage = aux_get_pg_storage().await?;
+
+        let projection = vec!["movies.movie.title".to_string()];
+
+        let search_manager = SearchServiceManager::new(db_storage).await;
+
+        let case_sensitive_result = search_manager
+            .search(projection.clone(), Filters::Expression("movies.person.person_name eq wagner moura".to_string()))
+            .await?;
+        let case_insensitive_result = search_manager
+            .search(projection, Filters::Expression("movies.person.person_name ieq wagner moura".to_string()))
+            .await?;
+
+        assert_eq!(case_sensitive_result["search_result"],json!([]));
+        assert_ne!(case_insensitive_result["search_result"],json!([]));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_search_service_pg_ignoring_accents() -> Result<(), Error> {
+        let db_storage = aux_get_pg_storage().await?;
+        let supports_unaccent = db_storage.supports_unaccent();
+
+        let search_manager = SearchServiceManager::new(db_storage).await;
+
+        let properties = serde_json::to_value(search_manager.get_filter_properties().await?)?;
+        assert_eq!(properties["ignore_accents"], json!(supports_unaccent));
+
+        let options = SearchOptions {
+            ignore_accents: true,
+            ..Default::default()
+        };
+        let search_result = search_manager
+            .search_with_options(
+                vec!["movies.movie.title".to_string()],
+                Filters::Expression("movies.person.person_name ieq wagner moura".to_string()),
+                options,
+            )
+            .await;
+
+        match supports_unaccent {
+            true => assert_ne!(search_result?["search_result"], json!([])),
+            false => assert!(matches!(search_result, Err(ManagerError::UnsupportedSearch(_)))),
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_search_service_pg_with_list() -> Result<(), Error> {
+        let db_storage = aux_get_pg_storage().await?;
+
+        let projection = vec!["movies.country.country_name".to_string()];
+        let filters = Filters::Expression("movies.country.country_name in (Brazil, United States of America)".to_string());
+
+        let search_manager = SearchServiceManager::new(db_storage).await;
+
+        let search_result = search_manager.search(projection,filters).await?;
+
+        let countries = search_result["search_result"].as_array().unwrap();
+        assert_eq!(countries.len(), 2);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_search_service_pg_with_range() -> Result<(), Error> {
+        let db_storage = aux_get_pg_storage().await?;
+
+        let projection = vec!["movies.movie.title".to_string(), "movies.movie.runtime".to_string()];
+        let filters = Filters::Expression("movies.movie.runtime between 240 AND 250".to_string());
+
+        let search_manager = SearchServiceManager::new(db_storage).await;
+
+        let search_result = search_manager.search(projection,filters).await?;
+
+        let movies = search_result["search_result"].as_array().unwrap();
+        assert!(!movies.is_empty());
+        for movie in movies {
+            let runtime: i64 = movie["runtime"].as_str().unwrap().parse()?;
+            assert!((240..=250).contains(&runtime));
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_search_service_pg_with_null_check() -> Result<(), Error> {
+        let db_storage = aux_get_pg_storage().await?;
+
+        let projection = vec!["movies.movie.title".to_string()];
+
+        let search_manager = SearchServiceManager::new(db_storage).await;
+
+        // every movie of the sample data has a title
+        let filters = Filters::Expression("movies.movie.title is_null".to_string());
+        let search_result = search_manager.search(projection.clone(),filters).await?;
+        assert_eq!(search_result["search_result"],json!([]));
+
+        let filters = Filters::Expression("movies.movie.title is_not_null".to_string());
+        let search_result = search_manager.search(projection,filters).await?;
+        assert_ne!(search_result["search_result"],json!([]));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_search_service_pg_with_schema_types() -> Result<(), Error> {
+        let db_storage = aux_get_pg_storage().await?;
+
+        let search_manager = SearchServiceManager::new(db_storage).await;
+
+        let projection = vec!["movies.movie.title".to_string()];
+        let filters = Filters::Expression("movies.movie.title eq 2046".to_string());
+        let search_result = search_manager.search(projection.clone(),filters).await?;
+        assert_eq!(search_result["search_result"],json!([{"title": "2046"}]));
+
+        let filters = Filters::Expression("movies.movie.homepage eq http://www.wkw2046.com/ AND movies.movie.release_date eq 2004-05-20".to_string());
+        let search_result = search_manager.search(projection.clone(),filters).await?;
+        assert_eq!(search_result["search_result"],json!([{"title": "2046"}]));
+
+        let filters = Filters::Expression("movies.movie.popularity gt 12.5 AND movies.movie.popularity lt 12.7 AND movies.movie.title eq 2046".to_string());
+        let search_result = search_manager.search(projection.clone(),filters).await?;
+        assert_eq!(search_result["search_result"],json!([{"title": "2046"}]));
+
+        let filters = Filters::Expression("movies.movie.runtime eq two hours".to_string());
+        let search_result = search_manager.search(projection.clone(),filters).await;
+        assert!(matches!(search_result, Err(ManagerError::TypeErrors(_))));
+
+        let filters = Filters::Expression("movies.movie.title eq movies.movie.budget OR movies.movie.release_date ge hello".to_string());
+        let search_result = search_manager.search(projection,filters).await;
+        match search_result {
+            Err(ManagerError::TypeErrors(errors)) => assert_eq!(errors.len(), 2),
+            _ => panic!("Expected type errors"),
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_search_service_pg_with_malformed_filters() -> Result<(), Error> {
+        let db_storage = aux_get_pg_storage().await?;
+
+        let search_manager = SearchServiceManager::new(db_storage).await;
+
+        let projection = vec!["movies.movie.title".to_string()];
+        let filters = Filters::Expression("(movies.movie.runtime gt 200 OR movies.movie.title eq".to_string());
+        let search_result = search_manager.search(projection,filters).await;
+
+        match search_result {
+            Err(ManagerError::ParseError(ParseError::UnexpectedEnd { offset, expected })) => {
+                assert_eq!(offset, 53);
+                assert_eq!(expected, "value");
+            }
+            _ => panic!("Expected a parse error"),
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_search_service_pg_with_quoted_literal() -> Result<(), Error> {
+        let db_storage = aux_get_pg_storage().await?;
+
+        let search_manager = SearchServiceManager::new(db_storage).await;
+
+        let projection = vec!["movies.movie.title".to_string()];
+        let filters = Filters::Expression(r"movies.movie.title eq 'Ocean\'s Eleven'".to_string());
+        let search_result = search_manager.search(projection,filters).await?;
+
+        assert_eq!(search_result["search_result"],json!([{"title": "Ocean's Eleven"}]));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_search_service_pg_binds_values() -> Result<(), Error> {
+        let db_storage = aux_get_pg_storage().await?;
+
+        let search_manager = SearchServiceManager::new(db_storage).await;
+
+        let projection = vec!["movies.movie.title".to_string()];
+        let filters = Filters::Expression(r#"movies.movie.title eq "x' OR '1'='1""#.to_string());
+        let search_result = search_manager.search(projection,filters).await?;
+
+        assert_eq!(search_result["search_result"],json!([]));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_search_service_pg_with_unknown_attributes() -> Result<(), Error> {
+        let db_storage = aux_get_pg_storage().await?;
+
+        let search_manager = SearchServiceManager::new(db_storage).await;
+
+        let projection = vec![
+            "movies.movie.title".to_string(),
+            "movies.movie.title FROM movies.movie; DROP TABLE movies.movie; --".to_string(),
+            "pg_catalog.pg_user.passwd".to_string(),
+        ];
+        let filters = Filters::Expression("movies.movie.runtim gt 100 AND movies.movie.budget gt 1000 OR movies.movie.revenu gt movies.movie.budget".to_string());
+        let search_result = search_manager.search(projection,filters).await;
+
+        match search_result {
+            Err(ManagerError::UnknownAttributes(attributes)) => assert_eq!(
+                attributes,
+                vec![
+                    "movies.movie.title FROM movies.movie; DROP TABLE movies.movie; --".to_string(),
+                    "pg_catalog.pg_user.passwd".to_string(),
+                    "movies.movie.revenu".to_string(),
+                    "movies.movie.runtim".to_string(),
+                ]
+            ),
+            _ => panic!("Expected unknown attributes"),
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_search_service_pg_with_normalized_filters() -> Result<(), Error> {
+        let db_storage = aux_get_pg_storage().await?;
+
+        let search_manager = SearchServiceManager::new(db_storage).await;
+
+        let projection = vec!["movies.movie.title".to_string()];
+        let title_filter = json!({"comparison": {
+            "attribute": "movies.movie.title",
+            "operator": "EqualTo",
+            "value": "2046",
+            "data_type": "String"
+        }});
+
+        let filters = Filters::Expression("movies.movie.title eq 2046 AND (movies.movie.runtime gt 5 AND movies.movie.runtime lt 3 OR movies.movie.title eq 2046)".to_string());
+        let search_result = search_manager.search(projection.clone(),filters).await?;
+        assert_eq!(search_result["search_result"],json!([{"title": "2046"}]));
+        assert_eq!(search_result["filters"],title_filter);
+
+        let filters = Filters::Expression("movies.movie.title eq 2046 AND NOT (movies.movie.runtime lt 60 OR movies.movie.budget lt 10)".to_string());
+        let options = SearchOptions { normal_form: Some(NormalForm::Dnf), ..Default::default() };
+        let search_result = search_manager.search_with_options(projection.clone(),filters,options).await?;
+        assert_eq!(search_result["search_result"],json!([{"title": "2046"}]));
+        assert_eq!(search_result["filters"]["and"][0],title_filter);
+        assert_eq!(search_result["filters"]["and"][1]["not"]["comparison"]["value"],json!(60));
+
+        let filters = Filters::Expression("movies.movie.runtime is_null AND movies.movie.runtime gt 60".to_string());
+        let search_result = search_manager.search(projection.clone(),filters).await?;
+        assert_eq!(search_result["search_result"],json!([]));
+        assert_eq!(search_result["filters"],json!({"or": []}));
+
+        // the filters returned are accepted again
+        let filters: Filters = serde_json::from_value(search_result["filters"].clone())?;
+        let search_result = search_manager.search(projection,filters).await?;
+        assert_eq!(search_result["search_result"],json!([]));
+        assert_eq!(search_result["filters"],json!({"or": []}));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_search_service_pg_with_optional_tables() -> Result<(), Error> {
+        let db_storage = aux_get_pg_storage().await?;
+
+        let search_manager = SearchServiceManager::new(db_storage).await;
+
+        let projection = vec![
+            "movies.movie.title".to_string(),
+            "movies.person.person_name".to_string(),
+        ];
+        let filters = Filters::Expression("movies.movie.title eq Mad Hot Ballroom".to_string());
+
+        let search_result = search_manager.search(projection.clone(),filters.clone()).await?;
+        assert_eq!(search_result["search_result"],json!([]));
+
+        let options = SearchOptions {
+            optional_tables: vec!["movies.movie_cast".to_string()],
+            ..Default::default()
+        };
+        let search_result = search_manager.search_with_options(projection.clone(),filters.clone(),options).await?;
+        assert_eq!(search_result["search_result"],json!([{"title": "Mad Hot Ballroom", "person_name": null}]));
+
+        // the same rows whatever the order of the projection
+        let reversed_projection: Vec<String> = projection.iter().rev().cloned().collect();
+        let options = SearchOptions {
+            optional_tables: vec!["movies.movie_cast".to_string()],
+            ..Default::default()
+        };
+        let search_result = search_manager.search_with_options(reversed_projection,filters.clone(),options).await?;
+        assert_eq!(search_result["search_result"],json!([{"person_name": null, "title": "Mad Hot Ballroom"}]));
+
+        let options = SearchOptions {
+            optional_tables: vec!["movies.movie_casts".to_string()],
+            ..Default::default()
+        };
+        let search_result = search_manager.search_with_options(projection,filters,options).await;
+        assert!(matches!(search_result, Err(ManagerError::UnknownTables(_))));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_search_service_pg_with_order_by() -> Result<(), Error> {
+        let db_storage = aux_get_pg_storage().await?;
+
+        let search_manager = SearchServiceManager::new(db_storage).await;
+
+        let projection = vec!["movies.movie.title".to_string()];
+        let filters = Filters::Expression("movies.movie.title in (Avatar, Titanic, 2046)".to_string());
+
+        let options = SearchOptions {
+            order_by: vec![OrderBy {
+                attribute: "movies.movie.revenue".to_string(),
+                direction: Direction::Desc,
+                nulls: Some(NullsOrder::Last),
+            }],
+            ..Default::default()
+        };
+        let search_result = search_manager.search_with_options(projection.clone(),filters.clone(),options).await?;
+        assert_eq!(
+            search_result["search_result"],
+            json!([{"title": "Avatar"}, {"title": "Titanic"}, {"title": "2046"}])
+        );
+
+        let options = SearchOptions {
+            order_by: vec![OrderBy {
+                attribute: "movies.movie.revenu".to_string(),
+                direction: Direction::Asc,
+                nulls: None,
+            }],
+            ..Default::default()
+        };
+        let search_result = search_manager.search_with_options(projection,filters,options).await;
+        assert!(matches!(search_result, Err(ManagerError::UnknownAttributes(_))));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_search_service_pg_with_pagination() -> Result<(), Error> {
+        let db_storage = aux_get_pg_storage().await?;
+
+        let search_manager = SearchServiceManager::new(db_storage).await;
+
+        let projection = vec!["movies.movie.title".to_string()];
+        let filters = Filters::Expression("movies.movie.title starts_with 'Star Wars'".to_string());
+        let order_by = vec![OrderBy {
+            attribute: "movies.movie.title".to_string(),
+            direction: Direction::Asc,
+            nulls: None,
+        }];
+
+        let options = SearchOptions {
+            order_by: order_by.clone(),
+            page_size: Some(2),
+            page: Some(2),
+            ..Default::default()
+        };
+        let search_result = search_manager.search_with_options(projection.clone(),filters.clone(),options).await?;
+        assert_eq!(
+            search_result["search_result"],
+            json!([
+                {"title": "Star Wars: Episode I - The Phantom Menace"},
+                {"title": "Star Wars: Episode II - Attack of the Clones"},
+            ])
+        );
+        assert_eq!(
+            search_result["pagination"],
+            json!({"page_size": 2, "offset": 2, "total_count": 5, "has_more": true})
+        );
+
+        let options = SearchOptions {
+            order_by: order_by.clone(),
+            page_size: Some(2),
+            offset: Some(4),
+            ..Default::default()
+        };
+        let search_result = search_manager.search_with_options(projection.clone(),filters.clone(),options).await?;
+        assert_eq!(
+            search_result["search_result"],
+            json!([{"title": "Star Wars: Episode III - Revenge of the Sith"}])
+        );
+        assert_eq!(search_result["pagination"]["has_more"], json!(false));
+
+        let filters = Filters::Expression("movies.movie.movie_id gt 0".to_string());
+        let options = SearchOptions {
+            page_size: Some(MAX_PAGE_SIZE + 1),
+            ..Default::default()
+        };
+        let search_result = search_manager.search_with_options(projection.clone(),filters.clone(),options).await?;
+        assert_eq!(search_result["search_result"].as_array().map(|r| r.len()), Some(MAX_PAGE_SIZE as usize));
+        assert_eq!(
+            search_result["pagination"],
+            json!({"page_size": MAX_PAGE_SIZE, "offset": 0, "total_count": 4803, "has_more": true})
+        );
+
+        let options = SearchOptions {
+            page: Some(1),
+            offset: Some(0),
+            ..Default::default()
+        };
+        let search_result = search_manager.search_with_options(projection.clone(),filters,options).await;
+        assert!(matches!(search_result, Err(ManagerError::InvalidPagination(_))));
+
+        // the tables sorted by are counted too, the movie has four genres
+        let filters = Filters::Expression("movies.movie.title eq 2046".to_string());
+        let options = SearchOptions {
+            order_by: vec![OrderBy {
+                attribute: "movies.genre.genre_name".to_string(),
+                direction: Direction::Asc,
+                nulls: None,
+            }],
+            page_size: Some(3),
+            deduplication: Deduplication::None,
+            ..Default::default()
+        };
+        let search_result = search_manager.search_with_options(projection.clone(),filters.clone(),options).await?;
+        assert_eq!(search_result["search_result"].as_array().map(|r| r.len()), Some(3));
+        assert_eq!(
+            search_result["pagination"],
+            json!({"page_size": 3, "offset": 0, "total_count": 4, "has_more": true})
+        );
+
+        // and its five production countries, which are sorted by rather than only filtered
+        let options = SearchOptions {
+            order_by: vec![OrderBy {
+                attribute: "movies.country.country_name".to_string(),
+                direction: Direction::Asc,
+                nulls: None,
+            }],
+            ..Default::default()
+        };
+        let search_result = search_manager.search_with_options(projection,filters,options).await?;
+        assert_eq!(search_result["search_result"].as_array().map(|r| r.len()), Some(5));
+        assert_eq!(search_result["pagination"]["total_count"], json!(5));
+        assert_eq!(search_result["pagination"]["has_more"], json!(false));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_search_service_pg_with_cursor_pagination() -> Result<(), Error> {
+        let db_storage = aux_get_pg_storage().await?;
+
+        let search_manager = SearchServiceManager::new(db_storage).await;
+
+        let projection = vec!["movies.movie.title".to_string()];
+        let filters = Filters::Expression("movies.movie.title starts_with 'Star Wars'".to_string());
+        let order_by = vec![OrderBy {
+            attribute: "movies.movie.title".to_string(),
+            direction: Direction::Asc,
+            nulls: None,
+        }];
+
+        let mut cursor = None;
+        let mut pages = Vec::new();
+        loop {
+            let options = SearchOptions {
+                order_by: order_by.clone(),
+                page_size: Some(2),
+                paging: Paging::Cursor,
+                cursor: cursor.clone(),
+                ..Default::default()
+            };
+            let search_result = search_manager.search_with_options(projection.clone(),filters.clone(),options).await?;
+            assert_eq!(search_result["pagination"]["total_count"], json!(5));
+
+            pages.push(search_result["search_result"].clone());
+            match search_result["pagination"]["next_cursor"].as_str() {
+                Some(next_cursor) => cursor = Some(next_cursor.to_string()),
+                None => break,
+            }
+        }
+        assert_eq!(pages, vec![
+            json!([{"title": "Star Wars"}, {"title": "Star Wars: Clone Wars (Volume 1)"}]),
+            json!([
+                {"title": "Star Wars: Episode I - The Phantom Menace"},
+                {"title": "Star Wars: Episode II - Attack of the Clones"},
+            ]),
+            json!([{"title": "Star Wars: Episode III - Revenge of the Sith"}]),
+        ]);
+
+        // rows of a movie joined to its genres are only told apart by the genres
+        let projection = vec!["movies.movie.title".to_string(), "movies.genre.genre_name".to_string()];
+        let filters = Filters::Expression("movies.movie.title in (Avatar, Titanic)".to_string());
+
+        let search_result = search_manager.search(projection.clone(),filters.clone()).await?;
+        let mut all_rows = search_result["search_result"].as_array().cloned().unwrap_or_default();
+
+        let mut cursor = None;
+        let mut paged_rows = Vec::new();
+        loop {
+            let options = SearchOptions {
+                page_size: Some(3),
+                cursor: cursor.clone(),
+                paging: Paging::Cursor,
+                ..Default::default()
+            };
+            let search_result = search_manager.search_with_options(projection.clone(),filters.clone(),options).await?;
+
+            paged_rows.extend(search_result["search_result"].as_array().cloned().unwrap_or_default());
+            match search_result["pagination"]["next_cursor"].as_str() {
+                Some(next_cursor) => cursor = Some(next_cursor.to_string()),
+                None => break,
+            }
+        }
+        all_rows.sort_by_key(|r| r.to_string());
+        paged_rows.sort_by_key(|r| r.to_string());
+        assert_eq!(all_rows.len(), 7);
+        assert_eq!(paged_rows, all_rows);
+
+        // a cursor only resumes the order it was made for
+        let options = SearchOptions {
+            order_by,
+            cursor,
+            ..Default::default()
+        };
+        let search_result = search_manager.search_with_options(projection,filters,options).await;
+        assert!(matches!(search_result, Err(ManagerError::InvalidPagination(_))));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_search_service_pg_with_aggregates() -> Result<(), Error> {
+        let db_storage = aux_get_pg_storage().await?;
+
+        let search_manager = SearchServiceManager::new(db_storage).await;
+
+        let filters = Filters::Expression("movies.movie.title starts_with 'Star Wars'".to_string());
+        let aggregates = vec![
+            Aggregate {
+                function: AggregateFunction::CountDistinct,
+                attribute: Some("movies.movie.movie_id".to_string()),
+                alias: Some("movies".to_string()),
+            },
+            Aggregate {
+                function: AggregateFunction::Max,
+                attribute: Some("movies.movie.budget".to_string()),
+                alias: None,
+            },
+        ];
+
+        let options = SearchOptions {
+            aggregates: aggregates.clone(),
+            group_by: vec!["movies.genre.genre_name".to_string()],
+            order_by: vec![
+                OrderBy {
+                    attribute: "movies".to_string(),
+                    direction: Direction::Desc,
+                    nulls: None,
+                },
+                OrderBy {
+                    attribute: "movies.genre.genre_name".to_string(),
+                    direction: Direction::Asc,
+                    nulls: None,
+                },
+            ],
+            page_size: Some(4),
+            ..Default::default()
+        };
+        let search_result = search_manager
+            .search_with_options(vec!["movies.genre.genre_name".to_string()],filters.clone(),options)
+            .await?;
+        assert_eq!(
+            search_result["search_result"],
+            json!([
+                {"genre_name": "Action", "movies": "5", "max_budget": "120000000"},
+                {"genre_name": "Adventure", "movies": "5", "max_budget": "120000000"},
+                {"genre_name": "Science Fiction", "movies": "5", "max_budget": "120000000"},
+                {"genre_name": "Animation", "movies": "1", "max_budget": "0"},
+            ])
+        );
+        assert_eq!(search_result["pagination"]["total_count"], json!(5));
+
+        let options = SearchOptions {
+            aggregates: aggregates.clone(),
+            group_by: vec!["movies.genre.genre_name".to_string()],
+            having: Some(Filters::Expression("movies gt 1 AND max_budget ge 100000000".to_string())),
+            order_by: vec![OrderBy {
+                attribute: "movies.genre.genre_name".to_string(),
+                direction: Direction::Asc,
+                nulls: None,
+            }],
+            ..Default::default()
+        };
+        let search_result = search_manager
+            .search_with_options(vec!["movies.genre.genre_name".to_string()],filters.clone(),options)
+            .await?;
+        assert_eq!(
+            search_result["search_result"],
+            json!([
+                {"genre_name": "Action", "movies": "5", "max_budget": "120000000"},
+                {"genre_name": "Adventure", "movies": "5", "max_budget": "120000000"},
+                {"genre_name": "Science Fiction", "movies": "5", "max_budget": "120000000"},
+            ])
+        );
+        assert_eq!(search_result["pagination"]["total_count"], json!(3));
+        assert_eq!(search_result["having"]["and"][0]["comparison"]["attribute"], json!("movies"));
+
+        // groups can't be filtered by attributes that vary inside them
+        let options = SearchOptions {
+            aggregates: aggregates.clone(),
+            group_by: vec!["movies.genre.genre_name".to_string()],
+            having: Some(Filters::Expression("movies.movie.budget gt 1".to_string())),
+            ..Default::default()
+        };
+        let search_result = search_manager
+            .search_with_options(vec!["movies.genre.genre_name".to_string()],filters.clone(),options)
+            .await;
+        assert!(matches!(search_result, Err(ManagerError::InvalidAggregation(_))));
+
+        let options = SearchOptions {
+            aggregates: vec![Aggregate {
+                function: AggregateFunction::Count,
+                attribute: None,
+                alias: None,
+            }],
+            ..Default::default()
+        };
+        let search_result = search_manager.search_with_options(vec![],filters.clone(),options).await?;
+        assert_eq!(search_result["search_result"], json!([{"count": "5"}]));
+        assert_eq!(search_result["pagination"]["total_count"], json!(1));
+
+        // the titles are neither grouped nor aggregated
+        let options = SearchOptions {
+            aggregates: aggregates.clone(),
+            group_by: vec!["movies.genre.genre_name".to_string()],
+            ..Default::default()
+        };
+        let search_result = search_manager
+            .search_with_options(vec!["movies.movie.title".to_string()],filters.clone(),options)
+            .await;
+        assert!(matches!(search_result, Err(ManagerError::InvalidAggregation(_))));
+
+        let options = SearchOptions {
+            aggregates: vec![Aggregate {
+                function: AggregateFunction::Avg,
+                attribute: Some("movies.movie.title".to_string()),
+                alias: None,
+            }],
+            ..Default::default()
+        };
+        let search_result = search_manager.search_with_options(vec![],filters.clone(),options).await;
+        assert!(matches!(search_result, Err(ManagerError::InvalidAggregation(_))));
+
+        // groups without aggregates nor projected attributes have no column
+        let options = SearchOptions {
+            group_by: vec!["movies.genre.genre_name".to_string()],
+            ..Default::default()
+        };
+        let search_result = search_manager.search_with_options(vec![],filters,options).await;
+        assert!(matches!(search_result, Err(ManagerError::InvalidProjection(_))));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_search_service_pg_with_deduplication() -> Result<(), Error> {
+        let db_storage = aux_get_pg_storage().await?;
+
+        let search_manager = SearchServiceManager::new(db_storage).await;
+
+        // the movie was produced in both countries
+        let projection = vec!["movies.movie.title".to_string()];
+        let filters = Filters::Expression(
+            "movies.country.country_name in (France, Germany) AND movies.movie.title eq 'The Black Dahlia'".to_string(),
+        );
+
+        let search_result = search_manager.search(projection.clone(),filters.clone()).await?;
+        assert_eq!(search_result["search_result"], json!([{"title": "The Black Dahlia"}]));
+        assert_eq!(search_result["pagination"]["total_count"], json!(1));
+
+        let options = SearchOptions {
+            deduplication: Deduplication::None,
+            ..Default::default()
+        };
+        let search_result = search_manager.search_with_options(projection.clone(),filters.clone(),options).await?;
+        assert_eq!(
+            search_result["search_result"],
+            json!([{"title": "The Black Dahlia"}, {"title": "The Black Dahlia"}])
+        );
+        assert_eq!(search_result["pagination"]["total_count"], json!(2));
+
+        let order_by = vec![OrderBy {
+            attribute: "movies.movie.title".to_string(),
+            direction: Direction::Asc,
+            nulls: None,
+        }];
+        let options = SearchOptions {
+            deduplication: Deduplication::Distinct,
+            order_by,
+            ..Default::default()
+        };
+        let search_result = search_manager.search_with_options(projection.clone(),filters.clone(),options).await?;
+        assert_eq!(search_result["search_result"], json!([{"title": "The Black Dahlia"}]));
+        assert_eq!(search_result["pagination"]["total_count"], json!(1));
+
+        // distinct rows can't be sorted by attributes that aren't projected
+        let options = SearchOptions {
+            deduplication: Deduplication::Distinct,
+            order_by: vec![OrderBy {
+                attribute: "movies.movie.budget".to_string(),
+                direction: Direction::Asc,
+                nulls: None,
+            }],
+            ..Default::default()
+        };
+        let search_result = search_manager.search_with_options(projection,filters,options).await;
+        assert!(matches!(search_result, Err(ManagerError::InvalidDistinct(_))));
+
+        // groups count every joined row whatever the deduplication
+        let projection = vec!["movies.genre.genre_name".to_string()];
+        let filters = Filters::Expression("movies.country.country_name eq France".to_string());
+        let grouped_options = |deduplication: Deduplication| SearchOptions {
+            aggregates: vec![Aggregate {
+                function: AggregateFunction::Count,
+                attribute: None,
+                alias: Some("n".to_string()),
+            }],
+            group_by: projection.clone(),
+            order_by: vec![OrderBy {
+                attribute: "n".to_string(),
+                direction: Direction::Desc,
+                nulls: None,
+            }],
+            page_size: Some(2),
+            deduplication,
+            ..Default::default()
+        };
+        let expected = json!([
+            {"genre_name": "Drama", "n": "174"},
+            {"genre_name": "Thriller", "n": "97"},
+        ]);
+
+        for deduplication in [Deduplication::SemiJoin, Deduplication::None] {
+            let search_result = search_manager
+                .search_with_options(projection.clone(), filters.clone(), grouped_options(deduplication))
+                .await?;
+            assert_eq!(search_result["search_result"], expected);
+        }
+
+        let search_result = search_manager
+            .search_with_options(projection.clone(), filters, grouped_options(Deduplication::Distinct))
+            .await;
+        assert!(matches!(search_result, Err(ManagerError::InvalidDistinct(_))));
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_search_service_mysql() -> Result<(), Error> {
         let db_storage = aux_get_mysql_storage().await?;
@@ -171,7 +1464,7 @@ mod tests {
         let projection = vec![
             "movies.movie.title".to_string()
         ];
-        let filters = "movies.person.person_name eq Wagner Moura".to_string();
+        let filters = Filters::Expression("movies.person.person_name eq Wagner Moura".to_string());
 
         let search_manager = SearchServiceManager::new(db_storage).await;
 
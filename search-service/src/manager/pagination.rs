@@ -0,0 +1,442 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    manager::{ManagerError, SearchOptions},
+    query_representation::{
+        intermediary::{
+            composite_command::{CompositeCommand, LogicalOperator},
+            single_command::{Operator, SingleCommand, Value},
+            typing::{literal_has_type, AttributeTypes},
+            Command,
+        },
+        ultimate::{dialect::SqlDialect, Deduplication, Direction, NullsOrder, OrderBy, Page},
+    },
+    relational::entities::DbSchema,
+};
+
+// Rows returned by a search that doesn't choose its page size.
+pub const DEFAULT_PAGE_SIZE: u64 = 100;
+// Most rows returned by a search, bigger page sizes are reduced to it.
+pub const MAX_PAGE_SIZE: u64 = 1000;
+
+// How the next page of a search is asked for.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Paging {
+    // by the page number or the rows skipped
+    #[default]
+    Offset,
+    // by the cursor returned with the previous page
+    Cursor,
+}
+
+// Position of the last row of a page. Clients only see it encoded, as `next_cursor`.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct Cursor {
+    // keys the rows are sorted by, a cursor only resumes the same order
+    keys: Vec<OrderBy>,
+    // text of the keys on the last row, None when missing
+    values: Vec<Option<String>>,
+}
+
+impl Cursor {
+    pub fn new(sort_keys: &[OrderBy], values: Vec<Option<String>>) -> Self {
+        Self {
+            keys: sort_keys.to_vec(),
+            values,
+        }
+    }
+
+    pub fn encode(&self) -> Result<String, ManagerError> {
+        let json = serde_json::to_vec(self).map_err(anyhow::Error::from)?;
+        Ok(URL_SAFE_NO_PAD.encode(json))
+    }
+
+    pub fn decode(token: &str) -> Result<Self, ManagerError> {
+        let invalid = || ManagerError::InvalidPagination("malformed cursor".into());
+
+        let json = URL_SAFE_NO_PAD.decode(token).map_err(|_| invalid())?;
+        let cursor: Cursor = serde_json::from_slice(&json).map_err(|_| invalid())?;
+
+        if cursor.keys.len() != cursor.values.len() {
+            return Err(invalid());
+        }
+
+        Ok(cursor)
+    }
+}
+
+// The page is chosen either by its number or by the rows skipped, never both.
+// Cursor paging always starts after the cursor, so it skips no rows.
+pub fn get_page(options: &SearchOptions) -> Result<Page, ManagerError> {
+    let limit = options.page_size.unwrap_or(DEFAULT_PAGE_SIZE).min(MAX_PAGE_SIZE);
+    if limit == 0 {
+        return Err(ManagerError::InvalidPagination("page_size must be at least 1".into()));
+    }
+
+    let is_aggregated = !options.aggregates.is_empty() || !options.group_by.is_empty();
+    if uses_cursor(options) && (is_aggregated || options.deduplication == Deduplication::Distinct) {
+        return Err(ManagerError::InvalidPagination(
+            "cursor paging is not available for aggregated or distinct searches".into(),
+        ));
+    }
+
+    if uses_cursor(options) && (options.page.is_some() || options.offset.is_some()) {
+        return Err(ManagerError::InvalidPagination(
+            "page and offset can't be used with cursor paging".into(),
+        ));
+    }
+
+    let offset = match (options.page, options.offset) {
+        (Some(_), Some(_)) => {
+            return Err(ManagerError::InvalidPagination(
+                "page and offset can't be used together".into(),
+            ))
+        }
+        (Some(0), None) => {
+            return Err(ManagerError::InvalidPagination("page must be at least 1".into()))
+        }
+        (Some(page), None) => (page - 1)
+            .checked_mul(limit)
+            .ok_or_else(|| ManagerError::InvalidPagination("page is too big".into()))?,
+        (None, Some(offset)) => offset,
+        (None, None) => 0,
+    };
+
+    Ok(Page { limit, offset })
+}
+
+// Sending a cursor asks for cursor paging.
+pub fn uses_cursor(options: &SearchOptions) -> bool {
+    options.paging == Paging::Cursor || options.cursor.is_some()
+}
+
+// Rows are sorted by the requested attributes and then by the primary keys of every
+// table of the query, so no two rows share a position. Tables without primary keys
+// are told apart by all their attributes. Missing values are placed explicitly, as
+// the keyset predicate has to know where they are. Keys of native types without a
+// data type can't be compared to the cursor, so the search can't be paged by it.
+pub fn get_sort_keys(
+    order_by: &[OrderBy],
+    tables: &[String],
+    db_schema: &DbSchema,
+    attribute_types: &AttributeTypes,
+    dialect: &dyn SqlDialect,
+) -> Result<Vec<OrderBy>, ManagerError> {
+    let mut tables = tables.to_vec();
+    tables.sort();
+
+    let mut key_attributes: Vec<String> = Vec::new();
+    for table in db_schema
+        .tables
+        .iter()
+        .filter(|t| tables.contains(&format!("{}.{}", t.schema, t.name)))
+    {
+        if table.primary_keys.is_empty() {
+            key_attributes.extend(
+                table
+                    .attributes
+                    .iter()
+                    .map(|a| format!("{}.{}.{}", table.schema, table.name, a.name)),
+            );
+        } else {
+            key_attributes.extend(
+                table
+                    .primary_keys
+                    .iter()
+                    .map(|k| format!("{}.{}.{}", k.schema_name, k.table_name, k.attribute_name)),
+            );
+        }
+    }
+
+    let mut sort_keys: Vec<OrderBy> = order_by.to_vec();
+    for attribute in key_attributes {
+        if !sort_keys.iter().any(|k| k.attribute == attribute) {
+            sort_keys.push(OrderBy {
+                attribute,
+                direction: Direction::Asc,
+                nulls: None,
+            });
+        }
+    }
+
+    if let Some(key) = sort_keys.iter().find(|k| !attribute_types.contains_key(&k.attribute)) {
+        return Err(ManagerError::InvalidPagination(format!(
+            "{} can't be used to sort pages with a cursor",
+            key.attribute
+        )));
+    }
+
+    for key in sort_keys.iter_mut() {
+        if key.nulls.is_none() {
+            key.nulls = Some(default_nulls_order(&key.direction, dialect));
+        }
+    }
+
+    Ok(sort_keys)
+}
+
+// Where the database places missing values when the order doesn't tell.
+fn default_nulls_order(direction: &Direction, dialect: &dyn SqlDialect) -> NullsOrder {
+    match (direction, dialect.nulls_are_greatest()) {
+        (Direction::Asc, true) | (Direction::Desc, false) => NullsOrder::Last,
+        _ => NullsOrder::First,
+    }
+}
+
+// Rows after the cursor: those greater on the first key, or equal on it and greater
+// on the second one, and so on. Greater follows the direction and the place of the
+// missing values of each key. Cursors come back from clients, so each value has to
+// fit the type of its key.
+pub fn keyset_command(
+    cursor: &Cursor,
+    sort_keys: &[OrderBy],
+    attribute_types: &AttributeTypes,
+) -> Result<Command, ManagerError> {
+    let invalid = || {
+        ManagerError::InvalidPagination("the cursor belongs to a search sorted differently".into())
+    };
+
+    if cursor.keys != sort_keys || cursor.values.len() != sort_keys.len() {
+        return Err(invalid());
+    }
+
+    let mut alternatives: Vec<Command> = Vec::new();
+    let mut equalities: Vec<Command> = Vec::new();
+
+    for (key, value) in sort_keys.iter().zip(cursor.values.iter()) {
+        let data_type = attribute_types.get(&key.attribute).ok_or_else(|| {
+            ManagerError::InvalidPagination(format!(
+                "{} can't be used to sort pages with a cursor",
+                key.attribute
+            ))
+        })?;
+        if let Some(value) = value {
+            if !literal_has_type(value, data_type) {
+                return Err(ManagerError::InvalidPagination(format!(
+                    "the cursor value {} doesn't fit the type of {} ({})",
+                    value, key.attribute, data_type
+                )));
+            }
+        }
+
+        let nulls_last = key.nulls != Some(NullsOrder::First);
+
+        let single = |operator: Operator, value: Value| {
+            Command::SingleCommand(SingleCommand::new(key.attribute.to_owned(), operator, value))
+        };
+        let composite = |logical_operator: LogicalOperator, commands: Vec<Command>| {
+            Command::CompositeCommand(CompositeCommand::new(logical_operator, commands))
+        };
+
+        let after = match value {
+            Some(value) => {
+                let operator = match key.direction {
+                    Direction::Asc => Operator::GreaterThan,
+                    Direction::Desc => Operator::LessThan,
+                };
+                let greater = single(operator, Value::new(value.to_owned(), data_type.to_owned()));
+
+                if nulls_last {
+                    composite(LogicalOperator::Or, vec![greater, single(Operator::IsNull, Value::empty())])
+                } else {
+                    greater
+                }
+            }
+            None if nulls_last => composite(LogicalOperator::Or, vec![]),
+            None => single(Operator::IsNotNull, Value::empty()),
+        };
+
+        let mut alternative = equalities.clone();
+        alternative.push(after);
+        alternatives.push(composite(LogicalOperator::And, alternative));
+
+        equalities.push(match value {
+            Some(value) => single(Operator::EqualTo, Value::new(value.to_owned(), data_type.to_owned())),
+            None => single(Operator::IsNull, Value::empty()),
+        });
+    }
+
+    Ok(Command::CompositeCommand(CompositeCommand::new(
+        LogicalOperator::Or,
+        alternatives,
+    )))
+}
+
+// Takes the cursor columns out of a row, returning their values.
+pub fn take_cursor_values(row: &mut serde_json::Value, len: usize) -> Vec<Option<String>> {
+    let Some(object) = row.as_object_mut() else {
+        return vec![None; len];
+    };
+
+    (0..len)
+        .map(|idx| {
+            object
+                .remove(&format!("cursor_{}", idx))
+                .and_then(|v| v.as_str().map(|s| s.to_owned()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use anyhow::Error;
+
+    use crate::query_representation::intermediary::single_command::DataType;
+    use crate::query_representation::ultimate::dialect::PostgresDialect;
+    use crate::relational::entities::{Attribute, PrimaryKey, Table};
+
+    fn sort_keys() -> Vec<OrderBy> {
+        vec![
+            OrderBy {
+                attribute: "movies.movie.revenue".into(),
+                direction: Direction::Desc,
+                nulls: Some(NullsOrder::Last),
+            },
+            OrderBy {
+                attribute: "movies.movie.movie_id".into(),
+                direction: Direction::Asc,
+                nulls: Some(NullsOrder::Last),
+            },
+        ]
+    }
+
+    fn attribute_types() -> AttributeTypes {
+        AttributeTypes::from([
+            ("movies.movie.revenue".to_string(), DataType::Integer),
+            ("movies.movie.movie_id".to_string(), DataType::Integer),
+        ])
+    }
+
+    #[test]
+    fn test_cursor_round_trip() -> Result<(), Error> {
+        let cursor = Cursor::new(&sort_keys(), vec![None, Some("19995".into())]);
+
+        assert_eq!(Cursor::decode(&cursor.encode()?)?, cursor);
+        assert!(matches!(
+            Cursor::decode("not a cursor"),
+            Err(ManagerError::InvalidPagination(_))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_keyset_command() -> Result<(), Error> {
+        let revenue = |operator: Operator, value: Value| {
+            Command::SingleCommand(SingleCommand::new("movies.movie.revenue".into(), operator, value))
+        };
+        let movie_id = |operator: Operator, value: Value| {
+            Command::SingleCommand(SingleCommand::new("movies.movie.movie_id".into(), operator, value))
+        };
+        let or = |commands| Command::CompositeCommand(CompositeCommand::new(LogicalOperator::Or, commands));
+        let and = |commands| Command::CompositeCommand(CompositeCommand::new(LogicalOperator::And, commands));
+
+        let cursor = Cursor::new(&sort_keys(), vec![Some("100".into()), Some("7".into())]);
+        assert_eq!(
+            keyset_command(&cursor, &sort_keys(), &attribute_types())?,
+            or(vec![
+                and(vec![or(vec![
+                    revenue(Operator::LessThan, Value::new("100".into(), DataType::Integer)),
+                    revenue(Operator::IsNull, Value::empty()),
+                ])]),
+                and(vec![
+                    revenue(Operator::EqualTo, Value::new("100".into(), DataType::Integer)),
+                    or(vec![
+                        movie_id(Operator::GreaterThan, Value::new("7".into(), DataType::Integer)),
+                        movie_id(Operator::IsNull, Value::empty()),
+                    ]),
+                ]),
+            ])
+        );
+
+        // nothing comes after a missing revenue but the following movie ids
+        let cursor = Cursor::new(&sort_keys(), vec![None, Some("7".into())]);
+        assert_eq!(
+            keyset_command(&cursor, &sort_keys(), &attribute_types())?,
+            or(vec![
+                and(vec![or(vec![])]),
+                and(vec![
+                    revenue(Operator::IsNull, Value::empty()),
+                    or(vec![
+                        movie_id(Operator::GreaterThan, Value::new("7".into(), DataType::Integer)),
+                        movie_id(Operator::IsNull, Value::empty()),
+                    ]),
+                ]),
+            ])
+        );
+
+        let cursor = Cursor::new(&sort_keys()[1..], vec![Some("7".into())]);
+        assert!(matches!(
+            keyset_command(&cursor, &sort_keys(), &attribute_types()),
+            Err(ManagerError::InvalidPagination(_))
+        ));
+
+        let cursor = Cursor::new(&sort_keys(), vec![Some("100".into())]);
+        assert!(matches!(
+            keyset_command(&cursor, &sort_keys(), &attribute_types()),
+            Err(ManagerError::InvalidPagination(_))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_sort_keys() -> Result<(), Error> {
+        let table = |name: &str, key: &str, key_type: &str| {
+            Table::new(
+                "movies".into(),
+                name.into(),
+                vec![Attribute::new(key.into(), key_type.into())],
+                vec![PrimaryKey::new("movies".into(), name.into(), key.into())],
+            )
+        };
+        let db_schema = DbSchema::new(
+            vec![table("movie", "movie_id", "integer"), table("ticket", "ticket_id", "uuid")],
+            vec![],
+        );
+        let order_by = &sort_keys()[..1];
+
+        let keys = get_sort_keys(
+            order_by,
+            &["movies.movie".to_string()],
+            &db_schema,
+            &attribute_types(),
+            &PostgresDialect,
+        )?;
+        assert_eq!(keys, sort_keys());
+
+        // the uuids of the tickets have no data type
+        assert!(matches!(
+            get_sort_keys(
+                order_by,
+                &["movies.movie".to_string(), "movies.ticket".to_string()],
+                &db_schema,
+                &attribute_types(),
+                &PostgresDialect,
+            ),
+            Err(ManagerError::InvalidPagination(_))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_keyset_command_with_edited_cursor() -> Result<(), Error> {
+        let cursor = Cursor::new(&sort_keys(), vec![Some("100".into()), Some("7".into())]);
+        let json = String::from_utf8(URL_SAFE_NO_PAD.decode(cursor.encode()?)?)?;
+
+        let edited = URL_SAFE_NO_PAD.encode(json.replace("\"100\"", "\"a lot\""));
+        assert!(matches!(
+            keyset_command(&Cursor::decode(&edited)?, &sort_keys(), &attribute_types()),
+            Err(ManagerError::InvalidPagination(_))
+        ));
+
+        Ok(())
+    }
+}
@@ -4,12 +4,16 @@ use strum::IntoEnumIterator;
 
 use serde::{Deserialize, Serialize};
 
-use std::{collections::HashSet, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
 
 use crate::{
     query_representation::intermediary::{
         composite_command::LogicalOperator,
         single_command::{DataType, Operator},
+        typing::operator_accepts_type,
     },
     relational::{entities::DbSchema, table_search::TableSearch},
     traits::SearchServiceStorage,
@@ -20,7 +24,38 @@ pub struct Properties {
     attributes: Vec<AttributeInfo>,
     subsets: Vec<HashSet<u8>>,
     operators: Vec<String>,
+    // operators accepted by the attributes of each data type
+    typed_operators: HashMap<DataType, TypedOperators>,
     logical_operators: Vec<String>,
+    // whether searches may ask case insensitive operators to also ignore accents
+    ignore_accents: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct TypedOperators {
+    operators: Vec<String>,
+    input: InputKind,
+}
+
+// How the values compared to an attribute are written.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum InputKind {
+    Number,
+    Text,
+    Date,
+}
+
+impl InputKind {
+    // attributes only have the data types that have an input kind
+    fn for_data_type(data_type: &DataType) -> Option<Self> {
+        match data_type {
+            DataType::Integer | DataType::Float => Some(InputKind::Number),
+            DataType::String => Some(InputKind::Text),
+            DataType::Date => Some(InputKind::Date),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -71,13 +106,16 @@ impl PropertiesManager {
                 &full_table_name,
                 &mut tables_subsets,
                 &mut attributes_subsets,
-                &table_search,
+                table_search,
             )?;
 
             for attribute in table.attributes.iter() {
                 let full_attr_name = format!("{}.{}", full_table_name, &attribute.name).to_string();
 
-                let data_type = self.storage.translate_native_type(&attribute.data_type)?;
+                // attributes that can't be searched aren't offered
+                let Ok(data_type) = self.storage.translate_native_type(&attribute.data_type) else {
+                    continue;
+                };
 
                 let attribute_info = AttributeInfo::new(full_attr_name, data_type, table_subset_id);
                 attributes_info_vec.push(attribute_info);
@@ -87,7 +125,22 @@ impl PropertiesManager {
             }
         }
 
-        let operators = Operator::iter().map(|o| o.clone().to_string()).collect();
+        let operators = Operator::iter()
+            .filter(|o| !o.is_range())
+            .map(|o| o.to_string())
+            .collect();
+
+        let typed_operators = DataType::iter()
+            .filter_map(|d| {
+                let input = InputKind::for_data_type(&d)?;
+                let operators = Operator::iter()
+                    .filter(|o| operator_accepts_type(o, &d))
+                    .map(|o| o.to_string())
+                    .collect();
+
+                Some((d, TypedOperators { operators, input }))
+            })
+            .collect();
 
         let logical_operators = LogicalOperator::iter()
             .map(|o| o.clone().to_string())
@@ -97,7 +150,9 @@ impl PropertiesManager {
             attributes: attributes_info_vec,
             subsets: attributes_subsets,
             operators,
+            typed_operators,
             logical_operators,
+            ignore_accents: self.storage.supports_unaccent(),
         })
     }
 }
@@ -113,7 +168,7 @@ impl PropertiesService {
         attributes_subsets: &mut Vec<HashSet<u8>>,
         table_search: &TableSearch,
     ) -> Result<u8, Error> {
-        let table_subset_id = self.find_table_subset_id(&table, &tables_subsets, &table_search)?;
+        let table_subset_id = self.find_table_subset_id(table, tables_subsets, table_search)?;
 
         if table_subset_id >= tables_subsets.len() as u8 {
             let mut new_hashset = HashSet::new();
@@ -137,13 +192,13 @@ impl PropertiesService {
     fn find_table_subset_id(
         &self,
         table: &str,
-        table_subsets: &Vec<HashSet<String>>,
+        table_subsets: &[HashSet<String>],
         table_search: &TableSearch,
     ) -> Result<u8, Error> {
         let mut subset_id: u8 = table_subsets.len() as u8;
 
         for (idx, subset) in table_subsets.iter().enumerate() {
-            if subset.len() > 0 {
+            if !subset.is_empty() {
                 let table_name = subset.iter().next().unwrap();
                 if self.are_tables_joinable(table, table_name, table_search)? {
                     subset_id = idx as u8;
@@ -244,13 +299,93 @@ mod tests {
                 "LessThan".to_string(),
                 "GreaterThanOrEqualTo".to_string(),
                 "LessThanOrEqualTo".to_string(),
-                "NotEqualTo".to_string()
+                "NotEqualTo".to_string(),
+                "Contains".to_string(),
+                "StartsWith".to_string(),
+                "EndsWith".to_string(),
+                "Like".to_string(),
+                "EqualToIgnoreCase".to_string(),
+                "NotEqualToIgnoreCase".to_string(),
+                "ContainsIgnoreCase".to_string(),
+                "StartsWithIgnoreCase".to_string(),
+                "EndsWithIgnoreCase".to_string(),
+                "LikeIgnoreCase".to_string(),
+                "In".to_string(),
+                "NotIn".to_string(),
+                "IsNull".to_string(),
+                "IsNotNull".to_string()
             ]
         );
 
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_typed_operators_creation_pg() -> Result<(), Error> {
+        let db_storage = aux_get_pg_storage().await?;
+        let db_schema = aux_get_db_schema(&db_storage).await?;
+        let table_search = aux_get_table_search(&db_schema)?;
+        let properties_manager = PropertiesManager::new(db_storage);
+        let properties = properties_manager
+            .get_filter_properties(&db_schema, &table_search)
+            .await?;
+
+        let ordered_operators: Vec<String> = vec![
+            "EqualTo".to_string(),
+            "GreaterThan".to_string(),
+            "LessThan".to_string(),
+            "GreaterThanOrEqualTo".to_string(),
+            "LessThanOrEqualTo".to_string(),
+            "NotEqualTo".to_string(),
+            "In".to_string(),
+            "NotIn".to_string(),
+            "Between".to_string(),
+            "IsNull".to_string(),
+            "IsNotNull".to_string(),
+        ];
+
+        let text_operators: Vec<String> = vec![
+            "EqualTo".to_string(),
+            "NotEqualTo".to_string(),
+            "Contains".to_string(),
+            "StartsWith".to_string(),
+            "EndsWith".to_string(),
+            "Like".to_string(),
+            "EqualToIgnoreCase".to_string(),
+            "NotEqualToIgnoreCase".to_string(),
+            "ContainsIgnoreCase".to_string(),
+            "StartsWithIgnoreCase".to_string(),
+            "EndsWithIgnoreCase".to_string(),
+            "LikeIgnoreCase".to_string(),
+            "In".to_string(),
+            "NotIn".to_string(),
+            "IsNull".to_string(),
+            "IsNotNull".to_string(),
+        ];
+
+        let mut typed_operators = HashMap::new();
+        typed_operators.insert(
+            DataType::Integer,
+            TypedOperators { operators: ordered_operators.clone(), input: InputKind::Number },
+        );
+        typed_operators.insert(
+            DataType::Float,
+            TypedOperators { operators: ordered_operators.clone(), input: InputKind::Number },
+        );
+        typed_operators.insert(
+            DataType::Date,
+            TypedOperators { operators: ordered_operators, input: InputKind::Date },
+        );
+        typed_operators.insert(
+            DataType::String,
+            TypedOperators { operators: text_operators, input: InputKind::Text },
+        );
+
+        assert_eq!(properties.typed_operators, typed_operators);
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_logical_operators_creation_mysql() -> Result<(), Error> {
         let db_storage = aux_get_mysql_storage().await?;
@@ -263,7 +398,7 @@ mod tests {
 
         assert_eq!(
             properties.logical_operators,
-            vec!["AND".to_string(), "OR".to_string(),]
+            vec!["AND".to_string(), "OR".to_string(), "NOT".to_string()]
         );
 
         Ok(())
@@ -286,9 +421,8 @@ mod tests {
             .collect();
 
         for attribute in properties.attributes {
-            assert_eq!(
-                possible_data_types.contains(&attribute.data_type.to_string()),
-                true
+            assert!(
+                possible_data_types.contains(&attribute.data_type.to_string())
             );
         }
 
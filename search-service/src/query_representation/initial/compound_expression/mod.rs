@@ -1,27 +1,31 @@
+use crate::traits::Expression;
+
 pub struct OrExpression {
-    pub left_expression: String,
-    pub right_expression: String,
+    pub expressions: Vec<Box<dyn Expression>>,
 }
 
 impl OrExpression {
-    pub fn new(left_expression: String, right_expression: String) -> Self {
-        Self {
-            left_expression,
-            right_expression,
-        }
+    pub fn new(expressions: Vec<Box<dyn Expression>>) -> Self {
+        Self { expressions }
     }
 }
 
 pub struct AndExpression {
-    pub left_expression: String,
-    pub right_expression: String,
+    pub expressions: Vec<Box<dyn Expression>>,
 }
 
 impl AndExpression {
-    pub fn new(left_expression: String, right_expression: String) -> Self {
-        Self {
-            left_expression,
-            right_expression,
-        }
+    pub fn new(expressions: Vec<Box<dyn Expression>>) -> Self {
+        Self { expressions }
+    }
+}
+
+pub struct NotExpression {
+    pub expression: Box<dyn Expression>,
+}
+
+impl NotExpression {
+    pub fn new(expression: Box<dyn Expression>) -> Self {
+        Self { expression }
     }
 }
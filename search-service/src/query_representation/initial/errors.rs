@@ -0,0 +1,20 @@
+use serde::Serialize;
+
+use thiserror::Error;
+
+#[derive(Error, Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ParseError {
+    #[error("Expected {expected} but found {token} at {offset}")]
+    UnexpectedToken {
+        token: String,
+        offset: usize,
+        expected: String,
+    },
+
+    #[error("Expected {expected} but the expression ended at {offset}")]
+    UnexpectedEnd { offset: usize, expected: String },
+
+    #[error("Invalid filter: {0}")]
+    InvalidFilter(String),
+}
@@ -0,0 +1,102 @@
+use serde::{Deserialize, Serialize};
+
+use crate::query_representation::intermediary::composite_command::LogicalOperator;
+use crate::query_representation::intermediary::single_command::{
+    DataType, Operator, SingleCommand, Value,
+};
+use crate::query_representation::intermediary::Command;
+
+// Structured alternative to the filter string: groups of nodes joined by a
+// logical operator, negated nodes and leaf comparisons carrying the type of their value.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[serde(rename_all = "snake_case")]
+pub enum FilterNode {
+    And(Vec<FilterNode>),
+    Or(Vec<FilterNode>),
+    Not(Box<FilterNode>),
+    Comparison(ComparisonNode),
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct ComparisonNode {
+    pub attribute: String,
+    pub operator: Operator,
+    // null checks take no value, so it may be left out
+    #[serde(default)]
+    pub value: serde_json::Value,
+    pub data_type: DataType,
+}
+
+impl ComparisonNode {
+    pub fn new(
+        attribute: String,
+        operator: Operator,
+        value: serde_json::Value,
+        data_type: DataType,
+    ) -> Self {
+        Self {
+            attribute,
+            operator,
+            value,
+            data_type,
+        }
+    }
+}
+
+// The tree of a command, so a command built by the service can be sent
+// back to clients in the same format they send filters.
+impl From<&Command> for FilterNode {
+    fn from(command: &Command) -> Self {
+        match command {
+            Command::CompositeCommand(composite_command) => {
+                let mut nodes: Vec<FilterNode> =
+                    composite_command.commands.iter().map(FilterNode::from).collect();
+
+                match composite_command.logical_operator {
+                    LogicalOperator::And => FilterNode::And(nodes),
+                    LogicalOperator::Or => FilterNode::Or(nodes),
+                    LogicalOperator::Not => FilterNode::Not(Box::new(nodes.remove(0))),
+                }
+            }
+            Command::SingleCommand(single_command) => {
+                FilterNode::Comparison(ComparisonNode::from(single_command))
+            }
+        }
+    }
+}
+
+impl From<&SingleCommand> for ComparisonNode {
+    fn from(single_command: &SingleCommand) -> Self {
+        let value = &single_command.value;
+
+        let (json_value, data_type) = match value.data_type {
+            DataType::List | DataType::Range => (
+                serde_json::Value::Array(value.list.iter().map(value_to_json_value).collect()),
+                value
+                    .list
+                    .first()
+                    .map(|v| v.data_type.clone())
+                    .unwrap_or(DataType::String),
+            ),
+            DataType::Null => (serde_json::Value::Null, DataType::Null),
+            _ => (value_to_json_value(value), value.data_type.clone()),
+        };
+
+        ComparisonNode::new(
+            single_command.attribute.to_owned(),
+            single_command.operator.clone(),
+            json_value,
+            data_type,
+        )
+    }
+}
+
+fn value_to_json_value(value: &Value) -> serde_json::Value {
+    let number = match value.data_type {
+        DataType::Integer => value.value.parse::<i64>().ok().map(serde_json::Value::from),
+        DataType::Float => value.value.parse::<f64>().ok().map(serde_json::Value::from),
+        _ => None,
+    };
+
+    number.unwrap_or_else(|| serde_json::Value::String(value.value.to_owned()))
+}
@@ -0,0 +1,244 @@
+/*
+    Module responsible for splitting a filter expression into tokens.
+    Parenthesis, commas, logical operators, bare words and quoted literals
+    are recognized, each one tagged with the character offset where
+    it starts in the original expression. Literals are enclosed in single
+    or double quotes and may contain the escape sequences \\, \", \',
+    \n and \t.
+*/
+
+use std::fmt;
+
+use crate::query_representation::initial::errors::ParseError;
+
+#[derive(PartialEq, Debug, Clone)]
+pub enum TokenKind {
+    LeftParenthesis,
+    RightParenthesis,
+    Comma,
+    And,
+    Or,
+    Not,
+    Word(String),
+    Literal(String),
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub offset: usize,
+}
+
+// the text of the token as written in the expression
+impl fmt::Display for TokenKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TokenKind::LeftParenthesis => write!(f, "("),
+            TokenKind::RightParenthesis => write!(f, ")"),
+            TokenKind::Comma => write!(f, ","),
+            TokenKind::And => write!(f, "AND"),
+            TokenKind::Or => write!(f, "OR"),
+            TokenKind::Not => write!(f, "NOT"),
+            TokenKind::Word(word) => write!(f, "{}", word),
+            TokenKind::Literal(literal) => write!(f, "{:?}", literal),
+        }
+    }
+}
+
+impl Token {
+    pub fn new(kind: TokenKind, offset: usize) -> Self {
+        Self { kind, offset }
+    }
+}
+
+pub fn tokenize(expression: &str) -> Result<Vec<Token>, ParseError> {
+    let mut tokens = Vec::new();
+    let mut chars = expression.chars().enumerate().peekable();
+    let length = expression.chars().count();
+
+    while let Some((offset, c)) = chars.next() {
+        match c {
+            '(' => tokens.push(Token::new(TokenKind::LeftParenthesis, offset)),
+            ')' => tokens.push(Token::new(TokenKind::RightParenthesis, offset)),
+            ',' => tokens.push(Token::new(TokenKind::Comma, offset)),
+            '"' | '\'' => {
+                let quote = c;
+                let mut literal = String::new();
+                let mut closed = false;
+
+                while let Some((escape_offset, c)) = chars.next() {
+                    match c {
+                        c if c == quote => {
+                            closed = true;
+                            break;
+                        }
+                        '\\' => match chars.next() {
+                            Some((_, escaped @ ('\\' | '"' | '\''))) => literal.push(escaped),
+                            Some((_, 'n')) => literal.push('\n'),
+                            Some((_, 't')) => literal.push('\t'),
+                            Some((_, escaped)) => {
+                                return Err(ParseError::UnexpectedToken {
+                                    token: format!("\\{}", escaped),
+                                    offset: escape_offset,
+                                    expected: "an escape sequence (\\\\, \\\", \\', \\n or \\t)".into(),
+                                })
+                            }
+                            None => break,
+                        },
+                        c => literal.push(c),
+                    }
+                }
+
+                if !closed {
+                    return Err(ParseError::UnexpectedEnd {
+                        offset: length,
+                        expected: format!("'{}' closing the literal at {}", quote, offset),
+                    });
+                }
+
+                tokens.push(Token::new(TokenKind::Literal(literal), offset));
+            }
+            c if c.is_whitespace() => {}
+            c => {
+                let mut word = c.to_string();
+
+                while let Some((_, next)) = chars.peek() {
+                    // apostrophes are kept inside words, as in O'Brien
+                    if next.is_whitespace() || matches!(next, '(' | ')' | ',' | '"') {
+                        break;
+                    }
+                    word.push(*next);
+                    chars.next();
+                }
+
+                let kind = match word.as_str() {
+                    "AND" => TokenKind::And,
+                    "OR" => TokenKind::Or,
+                    "NOT" => TokenKind::Not,
+                    _ => TokenKind::Word(word),
+                };
+
+                tokens.push(Token::new(kind, offset));
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use anyhow::Error;
+
+    #[test]
+    fn test_tokenize_terminal_expression() -> Result<(), Error> {
+        let tokens = tokenize("movies.movie.title eq Star Wars")?;
+
+        assert_eq!(
+            tokens,
+            vec![
+                Token::new(TokenKind::Word("movies.movie.title".into()), 0),
+                Token::new(TokenKind::Word("eq".into()), 19),
+                Token::new(TokenKind::Word("Star".into()), 22),
+                Token::new(TokenKind::Word("Wars".into()), 27),
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_tokenize_compound_expression() -> Result<(), Error> {
+        let tokens = tokenize("(a eq 1) OR(b eq 2)")?;
+
+        let kinds: Vec<TokenKind> = tokens.into_iter().map(|t| t.kind).collect();
+
+        assert_eq!(
+            kinds,
+            vec![
+                TokenKind::LeftParenthesis,
+                TokenKind::Word("a".into()),
+                TokenKind::Word("eq".into()),
+                TokenKind::Word("1".into()),
+                TokenKind::RightParenthesis,
+                TokenKind::Or,
+                TokenKind::LeftParenthesis,
+                TokenKind::Word("b".into()),
+                TokenKind::Word("eq".into()),
+                TokenKind::Word("2".into()),
+                TokenKind::RightParenthesis,
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_tokenize_quoted_literal() -> Result<(), Error> {
+        let tokens = tokenize("movies.movie.title eq \"Fast AND (Furious)\"")?;
+
+        assert_eq!(
+            tokens[2],
+            Token::new(TokenKind::Literal("Fast AND (Furious)".into()), 22)
+        );
+
+        assert_eq!(
+            tokenize("movies.movie.title eq \"Fast"),
+            Err(ParseError::UnexpectedEnd {
+                offset: 27,
+                expected: "'\"' closing the literal at 22".into(),
+            })
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_tokenize_literal_escapes() -> Result<(), Error> {
+        let tokens = tokenize(r#"'O\'Brien' "say \"hi\"" 'back\\slash' "tab\tnew\nline" 'quote"inside'"#)?;
+
+        let kinds: Vec<TokenKind> = tokens.into_iter().map(|t| t.kind).collect();
+
+        assert_eq!(
+            kinds,
+            vec![
+                TokenKind::Literal("O'Brien".into()),
+                TokenKind::Literal("say \"hi\"".into()),
+                TokenKind::Literal("back\\slash".into()),
+                TokenKind::Literal("tab\tnew\nline".into()),
+                TokenKind::Literal("quote\"inside".into()),
+            ]
+        );
+
+        assert_eq!(
+            tokenize(r#"'bad \x escape'"#),
+            Err(ParseError::UnexpectedToken {
+                token: "\\x".into(),
+                offset: 5,
+                expected: r#"an escape sequence (\\, \", \', \n or \t)"#.into(),
+            })
+        );
+
+        assert_eq!(
+            tokenize(r#"'O\'Brien"#),
+            Err(ParseError::UnexpectedEnd {
+                offset: 9,
+                expected: "''' closing the literal at 0".into(),
+            })
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_tokenize_apostrophe_in_word() -> Result<(), Error> {
+        let tokens = tokenize("movies.movie.title eq Schindler's List")?;
+
+        assert_eq!(tokens[2], Token::new(TokenKind::Word("Schindler's".into()), 22));
+
+        Ok(())
+    }
+}
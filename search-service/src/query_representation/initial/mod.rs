@@ -5,13 +5,25 @@
 */
 
 pub mod compound_expression;
+pub mod errors;
+pub mod filter_tree;
+pub mod lexer;
+pub mod parser;
 pub mod terminal_expression;
 mod tests;
 
 use crate::traits::Expression;
 
-use crate::query_representation::initial::compound_expression::{AndExpression, OrExpression};
-use crate::query_representation::initial::terminal_expression::TerminalExpression;
+use crate::query_representation::initial::compound_expression::{
+    AndExpression, NotExpression, OrExpression,
+};
+use crate::query_representation::initial::errors::ParseError;
+use crate::query_representation::initial::filter_tree::{ComparisonNode, FilterNode};
+use crate::query_representation::initial::lexer::tokenize;
+use crate::query_representation::initial::parser::Parser;
+use crate::query_representation::initial::terminal_expression::{
+    TerminalExpression, TerminalValue,
+};
 
 use crate::query_representation::intermediary::composite_command::{
     CompositeCommand, LogicalOperator,
@@ -21,11 +33,47 @@ use crate::query_representation::intermediary::single_command::{
 };
 use crate::query_representation::intermediary::Command;
 
-use anyhow::{Error, Ok};
+use anyhow::{anyhow, Error};
+
+use serde::Deserialize;
+
+// Names of the operators in the filter language.
+const OPERATOR_NAMES: &[(&str, Operator)] = &[
+    ("eq", Operator::EqualTo),
+    ("gt", Operator::GreaterThan),
+    ("lt", Operator::LessThan),
+    ("ge", Operator::GreaterThanOrEqualTo),
+    ("le", Operator::LessThanOrEqualTo),
+    ("ne", Operator::NotEqualTo),
+    ("contains", Operator::Contains),
+    ("starts_with", Operator::StartsWith),
+    ("ends_with", Operator::EndsWith),
+    ("like", Operator::Like),
+    ("ieq", Operator::EqualToIgnoreCase),
+    ("ine", Operator::NotEqualToIgnoreCase),
+    ("icontains", Operator::ContainsIgnoreCase),
+    ("istarts_with", Operator::StartsWithIgnoreCase),
+    ("iends_with", Operator::EndsWithIgnoreCase),
+    ("ilike", Operator::LikeIgnoreCase),
+    ("in", Operator::In),
+    ("not_in", Operator::NotIn),
+    ("between", Operator::Between),
+    ("is_null", Operator::IsNull),
+    ("is_not_null", Operator::IsNotNull),
+];
+
+// Filters of a search, either written in the filter language
+// or already structured as a tree by the client.
+#[derive(Deserialize, PartialEq, Debug, Clone)]
+#[serde(untagged)]
+pub enum Filters {
+    Expression(String),
+    Tree(FilterNode),
+}
 
 impl Expression for TerminalExpression {
     fn interpret(&self) -> Result<Command, Error> {
-        let simple_command = terminal_expression_to_simple_command(self.expression.to_owned())?;
+        let simple_command = terminal_expression_to_simple_command(self)?;
 
         Ok(simple_command)
     }
@@ -34,11 +82,8 @@ impl Expression for TerminalExpression {
 impl Expression for AndExpression {
     fn interpret(&self) -> Result<Command, Error> {
         let operation = LogicalOperator::And;
-        let composite_command = compound_expression_to_composite_command(
-            operation,
-            self.left_expression.to_owned(),
-            self.right_expression.to_owned(),
-        )?;
+        let composite_command =
+            compound_expression_to_composite_command(operation, &self.expressions)?;
 
         Ok(composite_command)
     }
@@ -47,146 +92,256 @@ impl Expression for AndExpression {
 impl Expression for OrExpression {
     fn interpret(&self) -> Result<Command, Error> {
         let operation = LogicalOperator::Or;
-        let composite_command = compound_expression_to_composite_command(
-            operation,
-            self.left_expression.to_owned(),
-            self.right_expression.to_owned(),
-        )?;
+        let composite_command =
+            compound_expression_to_composite_command(operation, &self.expressions)?;
 
         Ok(composite_command)
     }
 }
 
-pub fn initial_to_command(filters: String) -> Result<Command, Error> {
-    let expression = filters.replace('"', "");
-    println!("initial expression {}", expression);
+impl Expression for NotExpression {
+    fn interpret(&self) -> Result<Command, Error> {
+        let commands = vec![self.expression.interpret()?];
+        let composite_command = CompositeCommand::new(LogicalOperator::Not, commands);
 
-    let command = parse(expression)?;
+        Ok(Command::CompositeCommand(composite_command))
+    }
+}
+
+impl Expression for FilterNode {
+    fn interpret(&self) -> Result<Command, Error> {
+        let command = match self {
+            FilterNode::And(nodes) => filter_group_to_composite_command(LogicalOperator::And, nodes)?,
+            FilterNode::Or(nodes) => filter_group_to_composite_command(LogicalOperator::Or, nodes)?,
+            FilterNode::Not(node) => Command::CompositeCommand(CompositeCommand::new(
+                LogicalOperator::Not,
+                vec![node.interpret()?],
+            )),
+            FilterNode::Comparison(comparison) => comparison_node_to_simple_command(comparison)?,
+        };
+
+        Ok(command)
+    }
+}
+
+pub fn filters_to_command(filters: Filters) -> Result<Command, ParseError> {
+    let command = match filters {
+        Filters::Expression(expression) => initial_to_command(expression)?,
+        Filters::Tree(filter_node) => filter_node
+            .interpret()
+            .map_err(|e| ParseError::InvalidFilter(e.to_string()))?,
+    };
 
     Ok(command)
 }
 
-fn parse(expression: String) -> Result<Command, Error> {
-    let parts: Vec<&str>;
-    let command;
-
-    // in each compound expression parsed remove first ocurrence of '(' and last ocurrence of ')'
-    if expression.contains(") AND (") {
-        parts = expression.split(") AND (").collect();
-        let left_expression = parts[0].replace("(", "");
-        let mut right_expression = parts[1].to_string();
-        let last_occurence = parts[1].rfind(")").unwrap();
-        right_expression.remove(last_occurence);
-        let and_expression = AndExpression::new(left_expression, right_expression);
-        command = and_expression.interpret()?;
-    } else if expression.contains(") OR (") {
-        parts = expression.split(") OR (").collect();
-        let left_expression = parts[0].replace("(", "");
-        let mut right_expression = parts[1].to_string();
-        let last_occurence = parts[1].rfind(")").unwrap();
-        right_expression.remove(last_occurence);
-        let or_expression = OrExpression::new(left_expression, right_expression);
-        command = or_expression.interpret()?;
-    } else if expression.contains(") AND ") {
-        parts = expression.split(") AND ").collect();
-        let left_expression = parts[0].replace("(", "");
-        let right_expression = parts[1].to_string();
-        let or_expression = AndExpression::new(left_expression, right_expression);
-        command = or_expression.interpret()?;
-    } else if expression.contains(" AND (") {
-        parts = expression.split(" AND (").collect();
-        let left_expression = parts[0].to_string();
-        let mut right_expression = parts[1].to_string();
-        let last_occurence = parts[1].rfind(")").unwrap();
-        right_expression.remove(last_occurence);
-        let or_expression = AndExpression::new(left_expression, right_expression);
-        command = or_expression.interpret()?;
-    } else if expression.contains(" AND ") {
-        parts = expression.split(" AND ").collect();
-        let left_expression = parts[0].replace("(", "");
-        let right_expression = parts[1].to_string();
-        let and_expression = AndExpression::new(left_expression, right_expression);
-        command = and_expression.interpret()?;
-    } else if expression.contains(" OR (") {
-        parts = expression.split(" OR (").collect();
-        let left_expression = parts[0].to_string();
-        let mut right_expression = parts[1].to_string();
-        let last_occurence = parts[1].rfind(")").unwrap();
-        right_expression.remove(last_occurence);
-        let or_expression = OrExpression::new(left_expression, right_expression);
-        command = or_expression.interpret()?;
-    } else if expression.contains(") OR ") {
-        parts = expression.split(") OR ").collect();
-        let left_expression = parts[0].replace("(", "");
-        let right_expression = parts[1].to_string();
-        let or_expression = OrExpression::new(left_expression, right_expression);
-        command = or_expression.interpret()?;
-    } else if expression.contains(" OR ") {
-        parts = expression.split(" OR ").collect();
-        let left_expression = parts[0].replace("(", "");
-        let right_expression = parts[1].to_string();
-        let or_expression = OrExpression::new(left_expression, right_expression);
-        command = or_expression.interpret()?;
-    } else {
-        let terminal_expression = TerminalExpression::new(expression);
-        command = terminal_expression.interpret()?;
-    }
+pub fn initial_to_command(filters: String) -> Result<Command, ParseError> {
+    println!("initial expression {}", filters);
+
+    let command = parse(filters)?;
 
     Ok(command)
 }
 
+fn parse(expression: String) -> Result<Command, ParseError> {
+    let tokens = tokenize(&expression)?;
+    let parsed_expression = Parser::new(tokens, expression.chars().count()).parse()?;
+
+    let command = parsed_expression
+        .interpret()
+        .map_err(|e| ParseError::InvalidFilter(e.to_string()))?;
+
+    Ok(command)
+}
+
+fn operator_from_name(name: &str) -> Option<Operator> {
+    OPERATOR_NAMES
+        .iter()
+        .find(|(operator_name, _)| *operator_name == name)
+        .map(|(_, operator)| operator.clone())
+}
+
 fn compound_expression_to_composite_command(
     operation: LogicalOperator,
-    left_expression: String,
-    right_expression: String,
+    expressions: &[Box<dyn Expression>],
 ) -> Result<Command, Error> {
-    let mut commands = Vec::new();
+    let commands = expressions
+        .iter()
+        .map(|expression| expression.interpret())
+        .collect::<Result<Vec<Command>, Error>>()?;
 
-    commands.push(parse(left_expression)?);
-    commands.push(parse(right_expression)?);
+    Ok(flatten_composite_command(operation, commands))
+}
 
-    let composite_command = CompositeCommand::new(operation, commands);
+// Operands that apply the same operator, like a parenthesized chain, give
+// their commands to the composite command instead of nesting inside it.
+fn flatten_composite_command(operation: LogicalOperator, commands: Vec<Command>) -> Command {
+    let mut flattened_commands: Vec<Command> = Vec::new();
+
+    for command in commands {
+        match command {
+            Command::CompositeCommand(composite_command)
+                if composite_command.logical_operator == operation =>
+            {
+                flattened_commands.extend(composite_command.commands)
+            }
+            command => flattened_commands.push(command),
+        }
+    }
 
-    Ok(Command::CompositeCommand(composite_command))
+    Command::CompositeCommand(CompositeCommand::new(operation, flattened_commands))
 }
 
-fn terminal_expression_to_simple_command(expression: String) -> Result<Command, Error> {
-    let parts: Vec<&str> = expression.split(" ").collect();
+fn terminal_expression_to_simple_command(expression: &TerminalExpression) -> Result<Command, Error> {
+    let attribute = expression.attribute.to_owned();
+
+    let operator = operator_from_name(&expression.operator)
+        .ok_or_else(|| anyhow!("Wrong Operator type {}", expression.operator))?;
+
+    let value = match &expression.value {
+        TerminalValue::List(values) => Value::new_list(
+            values
+                .iter()
+                .map(|v| terminal_value_to_value(v, &operator))
+                .collect::<Result<Vec<Value>, Error>>()?,
+        ),
+        TerminalValue::Range(lower, upper) => Value::new_range(
+            terminal_value_to_value(lower, &operator)?,
+            terminal_value_to_value(upper, &operator)?,
+        ),
+        terminal_value => terminal_value_to_value(terminal_value, &operator)?,
+    };
 
-    let attribute = parts[0].to_string();
+    validate_value_for_operator(&operator, &value)?;
 
-    let operator = match parts[1] {
-        "eq" => Operator::EqualTo,
-        "gt" => Operator::GreaterThan,
-        "lt" => Operator::LessThan,
-        "ge" => Operator::GreaterThanOrEqualTo,
-        "le" => Operator::LessThanOrEqualTo,
-        "ne" => Operator::NotEqualTo,
-        &_ => panic!("{}",format!("Wrong Operator type {}", parts[1])),
-    };
+    let command = SingleCommand::new(attribute, operator, value);
 
-    let mut parsed_value: String = parts[2].trim().to_string();
-    for i in 3..parts.len() {
-        parsed_value.push_str(format!(" {}",parts[i]).as_str());
-    }
-    let value = match parsed_value.to_string().parse::<f64>().is_ok() {
-        true => Value::new(parsed_value.to_string(), DataType::Integer),
-        false => {
-            if string_is_attribute(parsed_value.to_string())? {
-                Value::new(parsed_value.to_string(), DataType::Attribute)
+    Ok(Command::SingleCommand(command))
+}
+
+fn terminal_value_to_value(terminal_value: &TerminalValue, operator: &Operator) -> Result<Value, Error> {
+    let value = match terminal_value {
+        TerminalValue::Quoted(parsed_value) => Value::new(parsed_value.to_owned(), DataType::String),
+        // literals are typed later on, from the attribute they are compared to
+        TerminalValue::Bare(parsed_value) => {
+            let parsed_value = parsed_value.to_owned();
+            if operator.is_pattern() {
+                Value::new(parsed_value, DataType::String)
+            } else if string_is_attribute(parsed_value.to_string())? {
+                Value::new(parsed_value, DataType::Attribute)
             } else {
-                Value::new(parsed_value.to_string(), DataType::String)
+                Value::new(parsed_value, DataType::String)
             }
         }
+        TerminalValue::Empty => Value::empty(),
+        TerminalValue::List(_) | TerminalValue::Range(_, _) => {
+            return Err(anyhow!("Lists and ranges can't be nested"))
+        }
     };
 
-    let command = SingleCommand::new(attribute, operator, value);
+    Ok(value)
+}
+
+// List values are only accepted, and required, by set membership operators,
+// the same goes for Range values and range operators and for Null values
+// and null check operators.
+fn validate_value_for_operator(operator: &Operator, value: &Value) -> Result<(), Error> {
+    let expected_data_type = if operator.is_set_membership() {
+        Some(DataType::List)
+    } else if operator.is_range() {
+        Some(DataType::Range)
+    } else if operator.is_null_check() {
+        Some(DataType::Null)
+    } else {
+        None
+    };
+
+    match (expected_data_type, &value.data_type) {
+        (Some(expected), data_type) if expected != *data_type => {
+            Err(anyhow!("Operator {} expects a {} value", operator, expected))
+        }
+        (None, DataType::List | DataType::Range | DataType::Null) => Err(anyhow!(
+            "Operator {} doesn't accept a {} value",
+            operator,
+            value.data_type
+        )),
+        _ => Ok(()),
+    }
+}
+
+// Groups become a single composite command, the same shape the filter
+// language parser produces for chains. A group of one node is that node, and
+// an empty group is the constant simplified filters may fold into: always
+// true for AND, never true for OR.
+fn filter_group_to_composite_command(
+    operation: LogicalOperator,
+    nodes: &[FilterNode],
+) -> Result<Command, Error> {
+    let mut commands = nodes
+        .iter()
+        .map(|node| node.interpret())
+        .collect::<Result<Vec<Command>, Error>>()?;
+
+    match commands.len() {
+        0 => Ok(Command::CompositeCommand(CompositeCommand::new(operation, vec![]))),
+        1 => Ok(commands.remove(0)),
+        _ => Ok(flatten_composite_command(operation, commands)),
+    }
+}
+
+fn comparison_node_to_simple_command(comparison: &ComparisonNode) -> Result<Command, Error> {
+    let value = match &comparison.value {
+        serde_json::Value::Array(values) if comparison.operator.is_range() => match values.as_slice() {
+            [lower, upper] => Value::new_range(
+                json_value_to_value(lower, comparison)?,
+                json_value_to_value(upper, comparison)?,
+            ),
+            _ => {
+                return Err(anyhow!(
+                    "Operator {} expects a lower and an upper bound",
+                    comparison.operator
+                ))
+            }
+        },
+        serde_json::Value::Null => Value::empty(),
+        serde_json::Value::Array(values) => Value::new_list(
+            values
+                .iter()
+                .map(|v| json_value_to_value(v, comparison))
+                .collect::<Result<Vec<Value>, Error>>()?,
+        ),
+        json_value => json_value_to_value(json_value, comparison)?,
+    };
+
+    validate_value_for_operator(&comparison.operator, &value)?;
+
+    let command = SingleCommand::new(
+        comparison.attribute.to_owned(),
+        comparison.operator.clone(),
+        value,
+    );
 
     Ok(Command::SingleCommand(command))
 }
 
+fn json_value_to_value(json_value: &serde_json::Value, comparison: &ComparisonNode) -> Result<Value, Error> {
+    let value = match json_value {
+        serde_json::Value::String(value) => value.to_owned(),
+        serde_json::Value::Number(value) => value.to_string(),
+        other => {
+            return Err(anyhow!(
+                "Unsupported value {} for attribute {}",
+                other,
+                comparison.attribute
+            ))
+        }
+    };
+
+    Ok(Value::new(value, comparison.data_type.clone()))
+}
+
 fn string_is_attribute(string: String) -> Result<bool, Error> {
-    let split_by_dot = string.split(".");
+    let split_by_dot = string.split('.');
     let collection = split_by_dot.collect::<Vec<&str>>();
 
     let is_attribute = collection.len() == 3;
@@ -206,7 +361,7 @@ mod private_tests {
         let simple_command = SingleCommand::new(
             "movies.movie.revenue".to_string(),
             Operator::GreaterThan,
-            Value::new(100000.to_string(), DataType::Integer),
+            Value::new(100000.to_string(), DataType::String),
         );
 
         let command = Command::SingleCommand(simple_command);
@@ -227,7 +382,7 @@ mod private_tests {
         let simple_command_1 = SingleCommand::new(
             "movies.movie.revenue".to_string(),
             Operator::GreaterThan,
-            Value::new(100000.to_string(), DataType::Integer),
+            Value::new(100000.to_string(), DataType::String),
         );
         let simple_command_2 = SingleCommand::new(
             "movies.movie.genre".to_string(),
@@ -258,7 +413,7 @@ mod private_tests {
         let simple_command_1 = SingleCommand::new(
             "movies.movie.revenue".to_string(),
             Operator::GreaterThan,
-            Value::new(100000.to_string(), DataType::Integer),
+            Value::new(100000.to_string(), DataType::String),
         );
         let simple_command_2 = SingleCommand::new(
             "movies.movie.genre".to_string(),
@@ -280,8 +435,16 @@ mod private_tests {
 
     #[test]
     fn test_compound_expression_to_composite_command() -> Result<(), Error> {
-        let left_expression = "movies.movie.release_date lt 01-01-2000".to_string();
-        let right_expression = "movies.movie.genre eq Comedy".to_string();
+        let left_expression: Box<dyn Expression> = Box::new(TerminalExpression::new(
+            "movies.movie.release_date".to_string(),
+            "lt".to_string(),
+            TerminalValue::Bare("01-01-2000".to_string()),
+        ));
+        let right_expression: Box<dyn Expression> = Box::new(TerminalExpression::new(
+            "movies.movie.genre".to_string(),
+            "eq".to_string(),
+            TerminalValue::Bare("Comedy".to_string()),
+        ));
 
         let operation = LogicalOperator::Or;
         let mut commands: Vec<Command> = Vec::new();
@@ -307,8 +470,7 @@ mod private_tests {
         assert_eq!(
             compound_expression_to_composite_command(
                 LogicalOperator::Or,
-                left_expression,
-                right_expression
+                &[left_expression, right_expression]
             )?,
             command
         );
@@ -318,24 +480,32 @@ mod private_tests {
 
     #[test]
     fn test_terminal_expression_to_simple_command() -> Result<(), Error> {
-        let expression = "movies.movie.revenue gt 100000".to_string();
+        let expression = TerminalExpression::new(
+            "movies.movie.revenue".to_string(),
+            "gt".to_string(),
+            TerminalValue::Bare("100000".to_string()),
+        );
 
         let simple_command = SingleCommand::new(
             "movies.movie.revenue".to_string(),
             Operator::GreaterThan,
-            Value::new(100000.to_string(), DataType::Integer),
+            Value::new(100000.to_string(), DataType::String),
         );
 
         let command = Command::SingleCommand(simple_command);
 
-        assert_eq!(terminal_expression_to_simple_command(expression)?, command);
+        assert_eq!(terminal_expression_to_simple_command(&expression)?, command);
 
         Ok(())
     }
 
     #[test]
     fn test_terminal_expression_to_simple_with_attr_as_value() -> Result<(), Error> {
-        let expression = "movies.movie.revenue lt movies.movie.budget".to_string();
+        let expression = TerminalExpression::new(
+            "movies.movie.revenue".to_string(),
+            "lt".to_string(),
+            TerminalValue::Bare("movies.movie.budget".to_string()),
+        );
 
         let simple_command = SingleCommand::new(
             "movies.movie.revenue".to_string(),
@@ -345,7 +515,7 @@ mod private_tests {
 
         let command = Command::SingleCommand(simple_command);
 
-        assert_eq!(terminal_expression_to_simple_command(expression)?, command);
+        assert_eq!(terminal_expression_to_simple_command(&expression)?, command);
 
         Ok(())
     }
@@ -353,13 +523,13 @@ mod private_tests {
     #[test]
     fn test_string_is_attribute() -> Result<(), Error> {
         let normal_string = "Disney".into();
-        assert_eq!(string_is_attribute(normal_string)?, false);
+        assert!(!string_is_attribute(normal_string)?);
 
         let attribute_string = "movies.movie.title".into();
-        assert_eq!(string_is_attribute(attribute_string)?, true);
+        assert!(string_is_attribute(attribute_string)?);
 
         let string_with_dot = "www.google.com.br".into();
-        assert_eq!(string_is_attribute(string_with_dot)?, false);
+        assert!(!string_is_attribute(string_with_dot)?);
 
         Ok(())
     }
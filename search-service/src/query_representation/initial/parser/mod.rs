@@ -0,0 +1,240 @@
+/*
+    Module responsible for building the expression tree of a filter
+    from its tokens. It is a recursive descent parser for the grammar
+
+        or_expression   := and_expression ( OR and_expression )*
+        and_expression  := not_expression ( AND not_expression )*
+        not_expression  := NOT not_expression | primary
+        primary         := '(' or_expression ')' | terminal
+        terminal        := WORD NULL_CHECK
+                         | WORD RANGE value AND value
+                         | WORD SET_MEMBERSHIP '(' value ( ',' value )* ')'
+                         | WORD OPERATOR value
+        value           := LITERAL | WORD+
+
+    so NOT binds tighter than AND, AND binds tighter than OR and
+    parenthesis may be nested freely. A chain of the same logical
+    operator becomes a single expression with every operand. The AND of a range belongs to
+    the terminal, it never starts a new and_expression. Operators are
+    words, the kind of operator tells which value follows it.
+*/
+
+use crate::traits::Expression;
+
+use crate::query_representation::initial::compound_expression::{
+    AndExpression, NotExpression, OrExpression,
+};
+use crate::query_representation::initial::errors::ParseError;
+use crate::query_representation::initial::lexer::{Token, TokenKind};
+use crate::query_representation::initial::{operator_from_name, OPERATOR_NAMES};
+use crate::query_representation::initial::terminal_expression::{
+    TerminalExpression, TerminalValue,
+};
+
+pub struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+    // offset where the expression ends, right after its last character
+    end_offset: usize,
+}
+
+impl Parser {
+    pub fn new(tokens: Vec<Token>, end_offset: usize) -> Self {
+        Self {
+            tokens,
+            position: 0,
+            end_offset,
+        }
+    }
+
+    pub fn parse(mut self) -> Result<Box<dyn Expression>, ParseError> {
+        let expression = self.parse_or_expression()?;
+
+        if self.peek().is_some() {
+            return Err(self.unexpected("AND, OR or the end of the expression"));
+        }
+
+        Ok(expression)
+    }
+
+    fn parse_or_expression(&mut self) -> Result<Box<dyn Expression>, ParseError> {
+        let mut expressions = vec![self.parse_and_expression()?];
+
+        while self.next_if(TokenKind::Or) {
+            expressions.push(self.parse_and_expression()?);
+        }
+
+        match expressions.len() {
+            1 => Ok(expressions.remove(0)),
+            _ => Ok(Box::new(OrExpression::new(expressions))),
+        }
+    }
+
+    fn parse_and_expression(&mut self) -> Result<Box<dyn Expression>, ParseError> {
+        let mut expressions = vec![self.parse_not_expression()?];
+
+        while self.next_if(TokenKind::And) {
+            expressions.push(self.parse_not_expression()?);
+        }
+
+        match expressions.len() {
+            1 => Ok(expressions.remove(0)),
+            _ => Ok(Box::new(AndExpression::new(expressions))),
+        }
+    }
+
+    fn parse_not_expression(&mut self) -> Result<Box<dyn Expression>, ParseError> {
+        if !self.next_if(TokenKind::Not) {
+            return self.parse_primary();
+        }
+
+        let expression = self.parse_not_expression()?;
+
+        Ok(Box::new(NotExpression::new(expression)))
+    }
+
+    fn parse_primary(&mut self) -> Result<Box<dyn Expression>, ParseError> {
+        if !self.next_if(TokenKind::LeftParenthesis) {
+            return self.parse_terminal();
+        }
+
+        let expression = self.parse_or_expression()?;
+
+        if !self.next_if(TokenKind::RightParenthesis) {
+            return Err(self.unexpected("')'"));
+        }
+
+        Ok(expression)
+    }
+
+    fn parse_terminal(&mut self) -> Result<Box<dyn Expression>, ParseError> {
+        let attribute = self.next_word("attribute")?;
+
+        let operator_hint = || {
+            let names: Vec<&str> = OPERATOR_NAMES.iter().map(|(name, _)| *name).collect();
+            format!("an operator ({})", names.join(", "))
+        };
+
+        let Some(operator) = self.peek_word().and_then(|word| operator_from_name(&word)) else {
+            return Err(self.unexpected(&operator_hint()));
+        };
+        let operator_name = self.next_word("operator")?;
+
+        let value = if operator.is_null_check() {
+            TerminalValue::Empty
+        } else if operator.is_range() {
+            self.parse_range()?
+        } else if operator.is_set_membership() {
+            if !self.next_if(TokenKind::LeftParenthesis) {
+                return Err(self.unexpected("'(' opening a list of values"));
+            }
+            self.parse_list()?
+        } else {
+            self.parse_value(false)?
+        };
+
+        let terminal_expression = TerminalExpression::new(attribute, operator_name, value);
+
+        Ok(Box::new(terminal_expression))
+    }
+
+    fn parse_list(&mut self) -> Result<TerminalValue, ParseError> {
+        let mut values = vec![self.parse_value(true)?];
+
+        while self.next_if(TokenKind::Comma) {
+            values.push(self.parse_value(true)?);
+        }
+
+        if !self.next_if(TokenKind::RightParenthesis) {
+            return Err(self.unexpected("',' or ')'"));
+        }
+
+        Ok(TerminalValue::List(values))
+    }
+
+    fn parse_range(&mut self) -> Result<TerminalValue, ParseError> {
+        let lower = self.parse_value(false)?;
+
+        if !self.next_if(TokenKind::And) {
+            return Err(self.unexpected("AND between the bounds of the range"));
+        }
+
+        let upper = self.parse_value(false)?;
+
+        Ok(TerminalValue::Range(Box::new(lower), Box::new(upper)))
+    }
+
+    // Outside of lists commas are part of the value, glued to the word before them.
+    fn parse_value(&mut self, in_list: bool) -> Result<TerminalValue, ParseError> {
+        let value = match self.peek().map(|t| t.kind.clone()) {
+            Some(TokenKind::Literal(literal)) => {
+                self.position += 1;
+                TerminalValue::Quoted(literal)
+            }
+            Some(TokenKind::Word(first_word)) => {
+                self.position += 1;
+                let mut value = first_word;
+
+                loop {
+                    match self.peek().map(|t| t.kind.clone()) {
+                        Some(TokenKind::Word(word)) => {
+                            value.push(' ');
+                            value.push_str(&word);
+                        }
+                        Some(TokenKind::Comma) if !in_list => value.push(','),
+                        _ => break,
+                    }
+                    self.position += 1;
+                }
+
+                TerminalValue::Bare(value)
+            }
+            _ => return Err(self.unexpected("value")),
+        };
+
+        Ok(value)
+    }
+
+    fn next_word(&mut self, expected: &str) -> Result<String, ParseError> {
+        match self.peek().map(|t| t.kind.clone()) {
+            Some(TokenKind::Word(word)) => {
+                self.position += 1;
+                Ok(word)
+            }
+            _ => Err(self.unexpected(expected)),
+        }
+    }
+
+    fn next_if(&mut self, kind: TokenKind) -> bool {
+        let matches = self.peek().is_some_and(|t| t.kind == kind);
+        if matches {
+            self.position += 1;
+        }
+        matches
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn peek_word(&self) -> Option<String> {
+        match self.peek().map(|t| &t.kind) {
+            Some(TokenKind::Word(word)) => Some(word.to_owned()),
+            _ => None,
+        }
+    }
+
+    fn unexpected(&self, expected: &str) -> ParseError {
+        match self.peek() {
+            Some(token) => ParseError::UnexpectedToken {
+                token: token.kind.to_string(),
+                offset: token.offset,
+                expected: expected.to_string(),
+            },
+            None => ParseError::UnexpectedEnd {
+                offset: self.end_offset,
+                expected: expected.to_string(),
+            },
+        }
+    }
+}
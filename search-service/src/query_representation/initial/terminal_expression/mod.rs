@@ -1,9 +1,28 @@
+#[derive(PartialEq, Debug, Clone)]
+pub enum TerminalValue {
+    // words as typed by the user, whose type is inferred
+    Bare(String),
+    // quoted values are always taken as literals, never as attributes or numbers
+    Quoted(String),
+    List(Vec<TerminalValue>),
+    // lower and upper bounds of a range
+    Range(Box<TerminalValue>, Box<TerminalValue>),
+    // operators that take no value
+    Empty,
+}
+
 pub struct TerminalExpression {
-    pub expression: String,
+    pub attribute: String,
+    pub operator: String,
+    pub value: TerminalValue,
 }
 
 impl TerminalExpression {
-    pub fn new(expression:String) -> Self {
-        Self {expression}
+    pub fn new(attribute: String, operator: String, value: TerminalValue) -> Self {
+        Self {
+            attribute,
+            operator,
+            value,
+        }
     }
-}
\ No newline at end of file
+}
@@ -1,4 +1,5 @@
 #[cfg(test)]
+#[allow(clippy::module_inception, clippy::vec_init_then_push)]
 pub mod tests {
 
     use crate::query_representation::intermediary::Command;
@@ -11,7 +12,9 @@ pub mod tests {
         CompositeCommand, LogicalOperator,
     };
 
-    use crate::query_representation::initial::initial_to_command;
+    use crate::query_representation::initial::errors::ParseError;
+    use crate::query_representation::initial::filter_tree::FilterNode;
+    use crate::query_representation::initial::{filters_to_command, initial_to_command, Filters};
 
     use anyhow::Error;
 
@@ -22,7 +25,7 @@ pub mod tests {
         let single_command = SingleCommand::new(
             "movies.movie.runtime".to_string(),
             Operator::GreaterThan,
-            Value::new(200.to_string(), DataType::Integer),
+            Value::new(200.to_string(), DataType::String),
         );
 
         let command = Command::SingleCommand(single_command);
@@ -76,12 +79,12 @@ pub mod tests {
         let single_command_1 = SingleCommand::new(
             "movies.movie.runtime".to_string(),
             Operator::GreaterThan,
-            Value::new(200.to_string(), DataType::Integer),
+            Value::new(200.to_string(), DataType::String),
         );
         let single_command_2 = SingleCommand::new(
             "movies.movie.revenue".to_string(),
             Operator::GreaterThan,
-            Value::new(1000000.to_string(), DataType::Integer),
+            Value::new(1000000.to_string(), DataType::String),
         );
 
         commands.push(Command::SingleCommand(single_command_1));
@@ -106,12 +109,12 @@ pub mod tests {
         let nested_single_command_1 = SingleCommand::new(
             "movies.movie.revenue".to_string(),
             Operator::GreaterThan,
-            Value::new(1000000.to_string(), DataType::Integer),
+            Value::new(1000000.to_string(), DataType::String),
         );
         let nested_single_command_2 = SingleCommand::new(
             "movies.movie.runtime".to_string(),
             Operator::GreaterThan,
-            Value::new(200.to_string(), DataType::Integer),
+            Value::new(200.to_string(), DataType::String),
         );
 
         nested_commands.push(Command::SingleCommand(nested_single_command_1));
@@ -122,7 +125,7 @@ pub mod tests {
         let single_command = SingleCommand::new(
             "movies.movie.runtime".to_string(),
             Operator::EqualTo,
-            Value::new(50.to_string(), DataType::Integer),
+            Value::new(50.to_string(), DataType::String),
         );
         let final_operation = LogicalOperator::And;
         let mut final_commands: Vec<Command> = Vec::new();
@@ -149,12 +152,12 @@ pub mod tests {
         let nested_single_command_1 = SingleCommand::new(
             "movies.movie.revenue".to_string(),
             Operator::GreaterThan,
-            Value::new(1000000.to_string(), DataType::Integer),
+            Value::new(1000000.to_string(), DataType::String),
         );
         let nested_single_command_2 = SingleCommand::new(
             "movies.movie.runtime".to_string(),
             Operator::GreaterThan,
-            Value::new(200.to_string(), DataType::Integer),
+            Value::new(200.to_string(), DataType::String),
         );
 
         nested_commands.push(Command::SingleCommand(nested_single_command_1));
@@ -165,7 +168,7 @@ pub mod tests {
         let single_command = SingleCommand::new(
             "movies.movie.runtime".to_string(),
             Operator::EqualTo,
-            Value::new(50.to_string(), DataType::Integer),
+            Value::new(50.to_string(), DataType::String),
         );
         let final_operation = LogicalOperator::And;
         let mut final_commands: Vec<Command> = Vec::new();
@@ -192,7 +195,7 @@ pub mod tests {
         let nested_single_command_1 = SingleCommand::new(
             "movies.movie.runtime".to_string(),
             Operator::EqualTo,
-            Value::new(50.to_string(), DataType::Integer),
+            Value::new(50.to_string(), DataType::String),
         );
         let nested_single_command_2 = SingleCommand::new(
             "movies.movie.release_date".to_string(),
@@ -203,49 +206,39 @@ pub mod tests {
         nested_commands_1.push(Command::SingleCommand(nested_single_command_1));
         nested_commands_1.push(Command::SingleCommand(nested_single_command_2));
 
-        let nested_composite_command_1 =
-            CompositeCommand::new(nested_operation_1, nested_commands_1);
-
         let nested_operation_2 = LogicalOperator::Or;
         let mut nested_commands_2: Vec<Command> = Vec::new();
 
         let nested_single_command_3 = SingleCommand::new(
             "movies.movie.revenue".to_string(),
             Operator::GreaterThan,
-            Value::new(1000000.to_string(), DataType::Integer),
+            Value::new(1000000.to_string(), DataType::String),
         );
         let nested_single_command_4 = SingleCommand::new(
             "movies.movie.runtime".to_string(),
             Operator::GreaterThan,
-            Value::new(200.to_string(), DataType::Integer),
+            Value::new(200.to_string(), DataType::String),
         );
 
         nested_commands_2.push(Command::SingleCommand(nested_single_command_3));
         nested_commands_2.push(Command::SingleCommand(nested_single_command_4));
 
-        let nested_composite_command_2 =
-            CompositeCommand::new(nested_operation_2, nested_commands_2);
-
         let single_command = SingleCommand::new(
             "movies.movie.revenue".to_string(),
             Operator::EqualTo,
-            Value::new(2000000.to_string(), DataType::Integer),
+            Value::new(2000000.to_string(), DataType::String),
         );
-        let nested_operation_3 = LogicalOperator::Or;
-        let mut nested_commands_3: Vec<Command> = Vec::new();
 
-        nested_commands_3.push(Command::CompositeCommand(nested_composite_command_2));
-        nested_commands_3.push(Command::SingleCommand(single_command));
+        // parenthesized chains of the same operator are flattened into their parent
+        nested_commands_2.push(Command::SingleCommand(single_command));
 
-        let nested_composite_command_3 =
-            CompositeCommand::new(nested_operation_3, nested_commands_3);
+        let nested_composite_command_2 =
+            CompositeCommand::new(nested_operation_2, nested_commands_2);
 
-        let final_operation = LogicalOperator::And;
-        let mut final_commands: Vec<Command> = Vec::new();
-        final_commands.push(Command::CompositeCommand(nested_composite_command_1));
-        final_commands.push(Command::CompositeCommand(nested_composite_command_3));
+        let mut final_commands: Vec<Command> = nested_commands_1;
+        final_commands.push(Command::CompositeCommand(nested_composite_command_2));
 
-        let final_nested_command = CompositeCommand::new(final_operation, final_commands);
+        let final_nested_command = CompositeCommand::new(nested_operation_1, final_commands);
 
         let command = Command::CompositeCommand(final_nested_command);
 
@@ -273,4 +266,816 @@ pub mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_initial_to_command_and_precedes_or() -> Result<(), Error> {
+        let filters = "movies.movie.runtime gt 200 OR movies.movie.revenue gt 1000000 AND movies.movie.budget lt 500".to_string();
+
+        let and_command = CompositeCommand::new(
+            LogicalOperator::And,
+            vec![
+                Command::SingleCommand(SingleCommand::new(
+                    "movies.movie.revenue".to_string(),
+                    Operator::GreaterThan,
+                    Value::new(1000000.to_string(), DataType::String),
+                )),
+                Command::SingleCommand(SingleCommand::new(
+                    "movies.movie.budget".to_string(),
+                    Operator::LessThan,
+                    Value::new(500.to_string(), DataType::String),
+                )),
+            ],
+        );
+
+        let or_command = CompositeCommand::new(
+            LogicalOperator::Or,
+            vec![
+                Command::SingleCommand(SingleCommand::new(
+                    "movies.movie.runtime".to_string(),
+                    Operator::GreaterThan,
+                    Value::new(200.to_string(), DataType::String),
+                )),
+                Command::CompositeCommand(and_command),
+            ],
+        );
+
+        assert_eq!(
+            initial_to_command(filters)?,
+            Command::CompositeCommand(or_command)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_initial_to_command_with_three_operands() -> Result<(), Error> {
+        let filters = "movies.movie.runtime gt 200 AND movies.movie.revenue gt 1000000 AND movies.movie.title eq Star Wars".to_string();
+
+        let command = CompositeCommand::new(
+            LogicalOperator::And,
+            vec![
+                Command::SingleCommand(SingleCommand::new(
+                    "movies.movie.runtime".to_string(),
+                    Operator::GreaterThan,
+                    Value::new(200.to_string(), DataType::String),
+                )),
+                Command::SingleCommand(SingleCommand::new(
+                    "movies.movie.revenue".to_string(),
+                    Operator::GreaterThan,
+                    Value::new(1000000.to_string(), DataType::String),
+                )),
+                Command::SingleCommand(SingleCommand::new(
+                    "movies.movie.title".to_string(),
+                    Operator::EqualTo,
+                    Value::new("Star Wars".to_string(), DataType::String),
+                )),
+            ],
+        );
+
+        assert_eq!(
+            initial_to_command(filters)?,
+            Command::CompositeCommand(command)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_initial_to_command_with_deep_nesting() -> Result<(), Error> {
+        let filters = "(((movies.movie.runtime gt 200) OR (movies.movie.runtime lt 50)) AND (movies.movie.budget gt 10))".to_string();
+
+        let or_command = CompositeCommand::new(
+            LogicalOperator::Or,
+            vec![
+                Command::SingleCommand(SingleCommand::new(
+                    "movies.movie.runtime".to_string(),
+                    Operator::GreaterThan,
+                    Value::new(200.to_string(), DataType::String),
+                )),
+                Command::SingleCommand(SingleCommand::new(
+                    "movies.movie.runtime".to_string(),
+                    Operator::LessThan,
+                    Value::new(50.to_string(), DataType::String),
+                )),
+            ],
+        );
+
+        let command = CompositeCommand::new(
+            LogicalOperator::And,
+            vec![
+                Command::CompositeCommand(or_command),
+                Command::SingleCommand(SingleCommand::new(
+                    "movies.movie.budget".to_string(),
+                    Operator::GreaterThan,
+                    Value::new(10.to_string(), DataType::String),
+                )),
+            ],
+        );
+
+        assert_eq!(
+            initial_to_command(filters)?,
+            Command::CompositeCommand(command)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_initial_to_command_with_quoted_value() -> Result<(), Error> {
+        let filters = "movies.movie.title eq \"Love AND (Actually)\" OR movies.movie.title eq \"1917\"".to_string();
+
+        let command = CompositeCommand::new(
+            LogicalOperator::Or,
+            vec![
+                Command::SingleCommand(SingleCommand::new(
+                    "movies.movie.title".to_string(),
+                    Operator::EqualTo,
+                    Value::new("Love AND (Actually)".to_string(), DataType::String),
+                )),
+                Command::SingleCommand(SingleCommand::new(
+                    "movies.movie.title".to_string(),
+                    Operator::EqualTo,
+                    Value::new("1917".to_string(), DataType::String),
+                )),
+            ],
+        );
+
+        assert_eq!(
+            initial_to_command(filters)?,
+            Command::CompositeCommand(command)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_initial_to_command_with_malformed_filters() {
+        let malformed_filters = vec![
+            "",
+            "(movies.movie.runtime gt 200",
+            "movies.movie.runtime gt 200)",
+            "movies.movie.runtime gt",
+            "movies.movie.runtime gt 200 AND",
+            "AND movies.movie.runtime gt 200",
+            "movies.movie.runtime gt 200 OR OR movies.movie.runtime lt 5",
+        ];
+
+        for filters in malformed_filters {
+            assert!(initial_to_command(filters.to_string()).is_err());
+        }
+    }
+
+    #[test]
+    fn test_filter_tree_to_command() -> Result<(), Error> {
+        let filters: Filters = serde_json::from_value(serde_json::json!({
+            "or": [
+                {"comparison": {
+                    "attribute": "movies.movie.title",
+                    "operator": "EqualTo",
+                    "value": "Love AND (Actually)",
+                    "data_type": "String"
+                }},
+                {"and": [
+                    {"comparison": {
+                        "attribute": "movies.movie.runtime",
+                        "operator": "GreaterThan",
+                        "value": 200,
+                        "data_type": "Integer"
+                    }},
+                    {"comparison": {
+                        "attribute": "movies.movie.revenue",
+                        "operator": "LessThan",
+                        "value": "movies.movie.budget",
+                        "data_type": "Attribute"
+                    }}
+                ]}
+            ]
+        }))?;
+
+        let and_command = CompositeCommand::new(
+            LogicalOperator::And,
+            vec![
+                Command::SingleCommand(SingleCommand::new(
+                    "movies.movie.runtime".to_string(),
+                    Operator::GreaterThan,
+                    Value::new(200.to_string(), DataType::Integer),
+                )),
+                Command::SingleCommand(SingleCommand::new(
+                    "movies.movie.revenue".to_string(),
+                    Operator::LessThan,
+                    Value::new("movies.movie.budget".to_string(), DataType::Attribute),
+                )),
+            ],
+        );
+
+        let or_command = CompositeCommand::new(
+            LogicalOperator::Or,
+            vec![
+                Command::SingleCommand(SingleCommand::new(
+                    "movies.movie.title".to_string(),
+                    Operator::EqualTo,
+                    Value::new("Love AND (Actually)".to_string(), DataType::String),
+                )),
+                Command::CompositeCommand(and_command),
+            ],
+        );
+
+        assert_eq!(filters_to_command(filters)?, Command::CompositeCommand(or_command));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_filter_tree_group_with_three_nodes() -> Result<(), Error> {
+        let comparison = |attribute: &str| {
+            serde_json::json!({"comparison": {
+                "attribute": attribute,
+                "operator": "EqualTo",
+                "value": 1,
+                "data_type": "Integer"
+            }})
+        };
+
+        let filters: Filters = serde_json::from_value(serde_json::json!({
+            "and": [comparison("a.b.c"), comparison("a.b.d"), comparison("a.b.e")]
+        }))?;
+
+        let single_command = |attribute: &str| {
+            Command::SingleCommand(SingleCommand::new(
+                attribute.to_string(),
+                Operator::EqualTo,
+                Value::new(1.to_string(), DataType::Integer),
+            ))
+        };
+
+        let command = CompositeCommand::new(
+            LogicalOperator::And,
+            vec![single_command("a.b.c"), single_command("a.b.d"), single_command("a.b.e")],
+        );
+
+        assert_eq!(filters_to_command(filters)?, Command::CompositeCommand(command));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_filters_accept_expression_string() -> Result<(), Error> {
+        let filters: Filters = serde_json::from_value(serde_json::json!("movies.movie.runtime gt 200"))?;
+
+        assert_eq!(
+            filters,
+            Filters::Expression("movies.movie.runtime gt 200".to_string())
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_filter_tree_with_invalid_nodes() -> Result<(), Error> {
+        let boolean_value: Filters = serde_json::from_value(serde_json::json!({"comparison": {
+            "attribute": "movies.movie.runtime",
+            "operator": "EqualTo",
+            "value": true,
+            "data_type": "Integer"
+        }}))?;
+        assert!(filters_to_command(boolean_value).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_filter_tree_with_constants() -> Result<(), Error> {
+        // filters folded into a constant are returned as empty groups and sent back as is
+        for logical_operator in [LogicalOperator::And, LogicalOperator::Or] {
+            let constant = Command::CompositeCommand(CompositeCommand::new(logical_operator, vec![]));
+
+            let tree = serde_json::to_value(FilterNode::from(&constant))?;
+            let filters: Filters = serde_json::from_value(tree)?;
+
+            assert_eq!(filters_to_command(filters)?, constant);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_initial_to_command_with_negations() -> Result<(), Error> {
+        let filters = "NOT (movies.country.country_name eq United States OR movies.country.country_name eq Brazil) AND NOT movies.movie.runtime lt 60".to_string();
+
+        let or_command = CompositeCommand::new(
+            LogicalOperator::Or,
+            vec![
+                Command::SingleCommand(SingleCommand::new(
+                    "movies.country.country_name".to_string(),
+                    Operator::EqualTo,
+                    Value::new("United States".to_string(), DataType::String),
+                )),
+                Command::SingleCommand(SingleCommand::new(
+                    "movies.country.country_name".to_string(),
+                    Operator::EqualTo,
+                    Value::new("Brazil".to_string(), DataType::String),
+                )),
+            ],
+        );
+
+        let not_single_command = CompositeCommand::new(
+            LogicalOperator::Not,
+            vec![Command::SingleCommand(SingleCommand::new(
+                "movies.movie.runtime".to_string(),
+                Operator::LessThan,
+                Value::new(60.to_string(), DataType::String),
+            ))],
+        );
+
+        let command = CompositeCommand::new(
+            LogicalOperator::And,
+            vec![
+                Command::CompositeCommand(CompositeCommand::new(
+                    LogicalOperator::Not,
+                    vec![Command::CompositeCommand(or_command)],
+                )),
+                Command::CompositeCommand(not_single_command),
+            ],
+        );
+
+        assert_eq!(
+            initial_to_command(filters)?,
+            Command::CompositeCommand(command)
+        );
+
+        assert!(initial_to_command("movies.movie.runtime lt 60 NOT".to_string()).is_err());
+        assert!(initial_to_command("NOT".to_string()).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_filter_tree_with_negation() -> Result<(), Error> {
+        let filters: Filters = serde_json::from_value(serde_json::json!({
+            "not": {"comparison": {
+                "attribute": "movies.country.country_name",
+                "operator": "EqualTo",
+                "value": "Brazil",
+                "data_type": "String"
+            }}
+        }))?;
+
+        let command = CompositeCommand::new(
+            LogicalOperator::Not,
+            vec![Command::SingleCommand(SingleCommand::new(
+                "movies.country.country_name".to_string(),
+                Operator::EqualTo,
+                Value::new("Brazil".to_string(), DataType::String),
+            ))],
+        );
+
+        assert_eq!(filters_to_command(filters)?, Command::CompositeCommand(command));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_initial_to_command_with_pattern_operators() -> Result<(), Error> {
+        let operators = vec![
+            ("contains", Operator::Contains),
+            ("starts_with", Operator::StartsWith),
+            ("ends_with", Operator::EndsWith),
+            ("like", Operator::Like),
+        ];
+
+        for (name, operator) in operators {
+            let filters = format!("movies.movie.title {} 1917", name);
+
+            let single_command = SingleCommand::new(
+                "movies.movie.title".to_string(),
+                operator,
+                Value::new("1917".to_string(), DataType::String),
+            );
+
+            assert_eq!(
+                initial_to_command(filters)?,
+                Command::SingleCommand(single_command)
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_initial_to_command_with_case_insensitive_operators() -> Result<(), Error> {
+        let filters = "movies.movie.title ieq 1917 OR movies.person.person_name icontains movies.movie_cast.character_name".to_string();
+
+        let command = CompositeCommand::new(
+            LogicalOperator::Or,
+            vec![
+                Command::SingleCommand(SingleCommand::new(
+                    "movies.movie.title".to_string(),
+                    Operator::EqualToIgnoreCase,
+                    Value::new("1917".to_string(), DataType::String),
+                )),
+                Command::SingleCommand(SingleCommand::new(
+                    "movies.person.person_name".to_string(),
+                    Operator::ContainsIgnoreCase,
+                    Value::new(
+                        "movies.movie_cast.character_name".to_string(),
+                        DataType::String,
+                    ),
+                )),
+            ],
+        );
+
+        assert_eq!(
+            initial_to_command(filters)?,
+            Command::CompositeCommand(command)
+        );
+
+        let filters = "movies.person.person_name ine movies.movie_cast.character_name".to_string();
+
+        let single_command = SingleCommand::new(
+            "movies.person.person_name".to_string(),
+            Operator::NotEqualToIgnoreCase,
+            Value::new(
+                "movies.movie_cast.character_name".to_string(),
+                DataType::Attribute,
+            ),
+        );
+
+        assert_eq!(
+            initial_to_command(filters)?,
+            Command::SingleCommand(single_command)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_initial_to_command_with_list() -> Result<(), Error> {
+        let filters = "movies.country.country_name in (Brazil, \"United States\", Korea, Republic of) AND movies.movie.runtime not_in (90,120)".to_string();
+
+        let in_command = SingleCommand::new(
+            "movies.country.country_name".to_string(),
+            Operator::In,
+            Value::new_list(vec![
+                Value::new("Brazil".to_string(), DataType::String),
+                Value::new("United States".to_string(), DataType::String),
+                Value::new("Korea".to_string(), DataType::String),
+                Value::new("Republic of".to_string(), DataType::String),
+            ]),
+        );
+
+        let not_in_command = SingleCommand::new(
+            "movies.movie.runtime".to_string(),
+            Operator::NotIn,
+            Value::new_list(vec![
+                Value::new("90".to_string(), DataType::String),
+                Value::new("120".to_string(), DataType::String),
+            ]),
+        );
+
+        let command = CompositeCommand::new(
+            LogicalOperator::And,
+            vec![
+                Command::SingleCommand(in_command),
+                Command::SingleCommand(not_in_command),
+            ],
+        );
+
+        assert_eq!(
+            initial_to_command(filters)?,
+            Command::CompositeCommand(command)
+        );
+
+        let malformed_filters = vec![
+            "movies.movie.runtime in 90",
+            "movies.movie.runtime eq (90, 120)",
+            "movies.movie.runtime in ()",
+            "movies.movie.runtime in (90, 120",
+            "movies.movie.runtime in (90,, 120)",
+        ];
+
+        for filters in malformed_filters {
+            assert!(initial_to_command(filters.to_string()).is_err());
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_initial_to_command_with_comma_in_value() -> Result<(), Error> {
+        let filters = "movies.movie.title eq Hello, World".to_string();
+
+        let single_command = SingleCommand::new(
+            "movies.movie.title".to_string(),
+            Operator::EqualTo,
+            Value::new("Hello, World".to_string(), DataType::String),
+        );
+
+        assert_eq!(
+            initial_to_command(filters)?,
+            Command::SingleCommand(single_command)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_filter_tree_with_list() -> Result<(), Error> {
+        let filters: Filters = serde_json::from_value(serde_json::json!({"comparison": {
+            "attribute": "movies.movie.runtime",
+            "operator": "In",
+            "value": [90, 120],
+            "data_type": "Integer"
+        }}))?;
+
+        let single_command = SingleCommand::new(
+            "movies.movie.runtime".to_string(),
+            Operator::In,
+            Value::new_list(vec![
+                Value::new("90".to_string(), DataType::Integer),
+                Value::new("120".to_string(), DataType::Integer),
+            ]),
+        );
+
+        assert_eq!(filters_to_command(filters)?, Command::SingleCommand(single_command));
+
+        let scalar_value: Filters = serde_json::from_value(serde_json::json!({"comparison": {
+            "attribute": "movies.movie.runtime",
+            "operator": "In",
+            "value": 90,
+            "data_type": "Integer"
+        }}))?;
+        assert!(filters_to_command(scalar_value).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_initial_to_command_with_range() -> Result<(), Error> {
+        let filters = "movies.movie.budget between 1000000 AND 10000000 AND movies.movie.release_date between \"2000-01-01\" AND \"2009-12-31\"".to_string();
+
+        let budget_command = SingleCommand::new(
+            "movies.movie.budget".to_string(),
+            Operator::Between,
+            Value::new_range(
+                Value::new("1000000".to_string(), DataType::String),
+                Value::new("10000000".to_string(), DataType::String),
+            ),
+        );
+
+        let release_date_command = SingleCommand::new(
+            "movies.movie.release_date".to_string(),
+            Operator::Between,
+            Value::new_range(
+                Value::new("2000-01-01".to_string(), DataType::String),
+                Value::new("2009-12-31".to_string(), DataType::String),
+            ),
+        );
+
+        let command = CompositeCommand::new(
+            LogicalOperator::And,
+            vec![
+                Command::SingleCommand(budget_command),
+                Command::SingleCommand(release_date_command),
+            ],
+        );
+
+        assert_eq!(
+            initial_to_command(filters)?,
+            Command::CompositeCommand(command)
+        );
+
+        let malformed_filters = vec![
+            "movies.movie.budget between 1000000",
+            "movies.movie.budget between 1000000 AND",
+            "movies.movie.budget between 1000000 OR 10000000",
+            "movies.movie.budget between (1000000, 10000000)",
+            "movies.movie.budget eq 1000000 AND 10000000",
+        ];
+
+        for filters in malformed_filters {
+            assert!(initial_to_command(filters.to_string()).is_err());
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_filter_tree_with_range() -> Result<(), Error> {
+        let filters: Filters = serde_json::from_value(serde_json::json!({"comparison": {
+            "attribute": "movies.movie.release_date",
+            "operator": "Between",
+            "value": ["2000-01-01", "2009-12-31"],
+            "data_type": "Date"
+        }}))?;
+
+        let single_command = SingleCommand::new(
+            "movies.movie.release_date".to_string(),
+            Operator::Between,
+            Value::new_range(
+                Value::new("2000-01-01".to_string(), DataType::Date),
+                Value::new("2009-12-31".to_string(), DataType::Date),
+            ),
+        );
+
+        assert_eq!(filters_to_command(filters)?, Command::SingleCommand(single_command));
+
+        let single_bound: Filters = serde_json::from_value(serde_json::json!({"comparison": {
+            "attribute": "movies.movie.release_date",
+            "operator": "Between",
+            "value": ["2000-01-01"],
+            "data_type": "Date"
+        }}))?;
+        assert!(filters_to_command(single_bound).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_initial_to_command_with_null_check() -> Result<(), Error> {
+        let filters = "movies.movie.homepage is_null OR NOT movies.movie.revenue is_not_null".to_string();
+
+        let is_null_command = SingleCommand::new(
+            "movies.movie.homepage".to_string(),
+            Operator::IsNull,
+            Value::empty(),
+        );
+
+        let is_not_null_command = SingleCommand::new(
+            "movies.movie.revenue".to_string(),
+            Operator::IsNotNull,
+            Value::empty(),
+        );
+
+        let command = CompositeCommand::new(
+            LogicalOperator::Or,
+            vec![
+                Command::SingleCommand(is_null_command),
+                Command::CompositeCommand(CompositeCommand::new(
+                    LogicalOperator::Not,
+                    vec![Command::SingleCommand(is_not_null_command)],
+                )),
+            ],
+        );
+
+        assert_eq!(
+            initial_to_command(filters)?,
+            Command::CompositeCommand(command)
+        );
+
+        let malformed_filters = vec![
+            "movies.movie.homepage is_null 10",
+            "movies.movie.homepage is_null (10)",
+            "movies.movie.homepage eq",
+        ];
+
+        for filters in malformed_filters {
+            assert!(initial_to_command(filters.to_string()).is_err());
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_filter_tree_with_null_check() -> Result<(), Error> {
+        let filters: Filters = serde_json::from_value(serde_json::json!({"comparison": {
+            "attribute": "movies.movie.homepage",
+            "operator": "IsNull",
+            "data_type": "String"
+        }}))?;
+
+        let single_command = SingleCommand::new(
+            "movies.movie.homepage".to_string(),
+            Operator::IsNull,
+            Value::empty(),
+        );
+
+        assert_eq!(filters_to_command(filters)?, Command::SingleCommand(single_command));
+
+        let with_value: Filters = serde_json::from_value(serde_json::json!({"comparison": {
+            "attribute": "movies.movie.homepage",
+            "operator": "IsNull",
+            "value": "",
+            "data_type": "String"
+        }}))?;
+        assert!(filters_to_command(with_value).is_err());
+
+        let without_value: Filters = serde_json::from_value(serde_json::json!({"comparison": {
+            "attribute": "movies.movie.homepage",
+            "operator": "EqualTo",
+            "data_type": "String"
+        }}))?;
+        assert!(filters_to_command(without_value).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_initial_to_command_parse_errors() -> Result<(), Error> {
+        let unexpected_token = |token: &str, offset: usize, expected: &str| ParseError::UnexpectedToken {
+            token: token.into(),
+            offset,
+            expected: expected.into(),
+        };
+
+        let cases = vec![
+            (
+                "movies.movie.runtime eq 90)",
+                unexpected_token(")", 26, "AND, OR or the end of the expression"),
+            ),
+            (
+                "movies.movie.runtime in 90",
+                unexpected_token("90", 24, "'(' opening a list of values"),
+            ),
+            (
+                "movies.movie.runtime between 90 OR 120",
+                unexpected_token("OR", 32, "AND between the bounds of the range"),
+            ),
+            (
+                "(movies.movie.runtime eq 90",
+                ParseError::UnexpectedEnd {
+                    offset: 27,
+                    expected: "')'".into(),
+                },
+            ),
+            (
+                "movies.movie.runtime eq",
+                ParseError::UnexpectedEnd {
+                    offset: 23,
+                    expected: "value".into(),
+                },
+            ),
+        ];
+
+        for (filters, parse_error) in cases {
+            assert_eq!(initial_to_command(filters.to_string()).unwrap_err(), parse_error);
+        }
+
+        match initial_to_command("movies.movie.runtime eqq 90".to_string()).unwrap_err() {
+            ParseError::UnexpectedToken {
+                token,
+                offset,
+                expected,
+            } => {
+                assert_eq!(token, "eqq");
+                assert_eq!(offset, 21);
+                assert!(expected.starts_with("an operator (eq, gt,"));
+            }
+            parse_error => panic!("Unexpected parse error {:?}", parse_error),
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_initial_to_command_with_escaped_literals() -> Result<(), Error> {
+        let cases = vec![
+            (r#"movies.movie.title eq 'Love (Actually)'"#, "Love (Actually)"),
+            (r#"movies.movie.title eq "O'Brien""#, "O'Brien"),
+            (r#"movies.movie.title eq 'O\'Brien'"#, "O'Brien"),
+            (r#"movies.movie.title eq "Fast AND Furious""#, "Fast AND Furious"),
+            (r#"movies.movie.title eq ' padded '"#, " padded "),
+            (r#"movies.movie.title eq "say \"hi\" \\o/""#, r#"say "hi" \o/"#),
+        ];
+
+        for (filters, literal) in cases {
+            let single_command = SingleCommand::new(
+                "movies.movie.title".to_string(),
+                Operator::EqualTo,
+                Value::new(literal.to_string(), DataType::String),
+            );
+
+            assert_eq!(
+                initial_to_command(filters.to_string())?,
+                Command::SingleCommand(single_command)
+            );
+        }
+
+        let filters = r#"movies.movie.title in ('Up', "It's Complicated") AND movies.movie.title ne 'A, B'"#;
+
+        let command = CompositeCommand::new(
+            LogicalOperator::And,
+            vec![
+                Command::SingleCommand(SingleCommand::new(
+                    "movies.movie.title".to_string(),
+                    Operator::In,
+                    Value::new_list(vec![
+                        Value::new("Up".to_string(), DataType::String),
+                        Value::new("It's Complicated".to_string(), DataType::String),
+                    ]),
+                )),
+                Command::SingleCommand(SingleCommand::new(
+                    "movies.movie.title".to_string(),
+                    Operator::NotEqualTo,
+                    Value::new("A, B".to_string(), DataType::String),
+                )),
+            ],
+        );
+
+        assert_eq!(
+            initial_to_command(filters.to_string())?,
+            Command::CompositeCommand(command)
+        );
+
+        Ok(())
+    }
 }
@@ -2,7 +2,7 @@ use strum_macros::{Display, EnumString, EnumIter};
 
 use crate::query_representation::intermediary::Command;
 
-#[derive(PartialEq, Debug)]
+#[derive(PartialEq, Debug, Clone)]
 pub struct CompositeCommand {
     pub logical_operator: LogicalOperator,
     pub commands: Vec<Command>,
@@ -14,6 +14,8 @@ pub struct CompositeCommand {
 pub enum LogicalOperator {
     And,
     Or,
+    // negates its single nested command
+    Not,
 }
 
 impl CompositeCommand {
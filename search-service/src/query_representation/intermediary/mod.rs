@@ -4,27 +4,30 @@
 */
 
 pub mod composite_command;
+pub mod normalization;
 pub mod single_command;
 pub mod tests;
+pub mod typing;
 
 use std::sync::Arc;
 
 use anyhow::Error;
 
+use crate::query_representation::ultimate::Query;
 use crate::traits::{Component, Visitor};
 
 use crate::query_representation::intermediary::single_command::{SingleCommand,DataType};
 
 use crate::query_representation::intermediary::composite_command::CompositeCommand;
 
-#[derive(PartialEq, Debug)]
+#[derive(PartialEq, Debug, Clone)]
 pub enum Command {
     SingleCommand(SingleCommand),
     CompositeCommand(CompositeCommand),
 }
 
 impl Component for Command {
-    fn accept(&self, projection: Vec<String>, v: Arc<dyn Visitor>) -> Result<String, Error> {
+    fn accept(&self, projection: Vec<String>, v: Arc<dyn Visitor>) -> Result<Query, Error> {
         let query = v.visit_command(projection, self)?;
 
         Ok(query)
@@ -36,14 +39,21 @@ pub fn get_command_attributes(command: &Command) -> Vec<String> {
         Command::CompositeCommand(cc) => cc
             .commands
             .iter()
-            .flat_map(|c| get_command_attributes(&c))
+            .flat_map(get_command_attributes)
             .collect::<Vec<String>>(),
         Command::SingleCommand(sc) => {
             let attribute = sc.attribute.to_owned();
 
             let mut attributes = vec![attribute];
-            if let DataType::Attribute = sc.value.data_type {
-               attributes.push(sc.value.value.to_owned());
+            let values = match sc.value.data_type {
+                DataType::List => sc.value.list.iter().collect(),
+                DataType::Range => sc.value.list.iter().collect(),
+                _ => vec![&sc.value],
+            };
+            for value in values {
+                if let DataType::Attribute = value.data_type {
+                    attributes.push(value.value.to_owned());
+                }
             }
 
             attributes
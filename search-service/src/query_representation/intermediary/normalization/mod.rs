@@ -0,0 +1,492 @@
+/*
+    Module responsible for simplifying a command before the query is
+    built. Nested groups of the same logical operator are flattened,
+    repeated commands are removed and comparisons that can't hold at
+    the same time are folded, so the query only carries the filters
+    that matter. The command may also be rewritten to a conjunctive or
+    disjunctive normal form.
+
+    An AND without nested commands is always true and an OR without
+    nested commands is always false, which is how folded commands are
+    represented.
+*/
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Error};
+use serde::{Deserialize, Serialize};
+
+use crate::query_representation::intermediary::composite_command::{
+    CompositeCommand, LogicalOperator,
+};
+use crate::query_representation::intermediary::single_command::{
+    DataType, Operator, SingleCommand, Value,
+};
+use crate::query_representation::intermediary::Command;
+
+// Largest number of groups a normal form may have, rewriting can grow a command exponentially.
+const MAX_NORMAL_FORM_GROUPS: usize = 256;
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[serde(rename_all = "lowercase")]
+pub enum NormalForm {
+    // an AND of ORs
+    Cnf,
+    // an OR of ANDs
+    Dnf,
+}
+
+pub fn normalize_command(command: Command, normal_form: Option<&NormalForm>) -> Result<Command, Error> {
+    let command = simplify_command(command);
+
+    let Some(normal_form) = normal_form else {
+        return Ok(command);
+    };
+
+    let (outer_operator, inner_operator) = match normal_form {
+        NormalForm::Cnf => (LogicalOperator::And, LogicalOperator::Or),
+        NormalForm::Dnf => (LogicalOperator::Or, LogicalOperator::And),
+    };
+
+    let groups = normal_form_groups(&negate_command(command, false), &outer_operator, &inner_operator)?;
+
+    let commands = groups
+        .into_iter()
+        .map(|group| Command::CompositeCommand(CompositeCommand::new(inner_operator.clone(), group)))
+        .collect();
+
+    Ok(simplify_command(Command::CompositeCommand(CompositeCommand::new(
+        outer_operator,
+        commands,
+    ))))
+}
+
+fn simplify_command(command: Command) -> Command {
+    let Command::CompositeCommand(composite_command) = command else {
+        return command;
+    };
+
+    let operator = composite_command.logical_operator;
+    let commands: Vec<Command> = composite_command
+        .commands
+        .into_iter()
+        .map(simplify_command)
+        .collect();
+
+    if let LogicalOperator::Not = operator {
+        return simplify_negation(commands);
+    }
+
+    let mut simplified_commands: Vec<Command> = Vec::new();
+
+    for command in commands {
+        match command {
+            Command::CompositeCommand(nested_command) if nested_command.logical_operator == operator => {
+                for nested_command in nested_command.commands {
+                    if !simplified_commands.contains(&nested_command) {
+                        simplified_commands.push(nested_command);
+                    }
+                }
+            }
+            // an always false command in an AND, or an always true one in an OR, decides the group
+            Command::CompositeCommand(nested_command)
+                if nested_command.commands.is_empty() && nested_command.logical_operator != LogicalOperator::Not =>
+            {
+                return Command::CompositeCommand(nested_command);
+            }
+            command => {
+                if !simplified_commands.contains(&command) {
+                    simplified_commands.push(command);
+                }
+            }
+        }
+    }
+
+    if let LogicalOperator::And = operator {
+        if has_contradiction(&simplified_commands) {
+            return constant_command(false);
+        }
+    }
+
+    match simplified_commands.len() {
+        1 => simplified_commands.remove(0),
+        _ => Command::CompositeCommand(CompositeCommand::new(operator, simplified_commands)),
+    }
+}
+
+fn simplify_negation(mut commands: Vec<Command>) -> Command {
+    if commands.len() != 1 {
+        return Command::CompositeCommand(CompositeCommand::new(LogicalOperator::Not, commands));
+    }
+
+    match commands.remove(0) {
+        Command::CompositeCommand(mut nested_command) => match nested_command.logical_operator {
+            LogicalOperator::Not if nested_command.commands.len() == 1 => nested_command.commands.remove(0),
+            LogicalOperator::And if nested_command.commands.is_empty() => constant_command(false),
+            LogicalOperator::Or if nested_command.commands.is_empty() => constant_command(true),
+            _ => Command::CompositeCommand(CompositeCommand::new(
+                LogicalOperator::Not,
+                vec![Command::CompositeCommand(nested_command)],
+            )),
+        },
+        command => Command::CompositeCommand(CompositeCommand::new(LogicalOperator::Not, vec![command])),
+    }
+}
+
+fn constant_command(value: bool) -> Command {
+    let operator = match value {
+        true => LogicalOperator::And,
+        false => LogicalOperator::Or,
+    };
+
+    Command::CompositeCommand(CompositeCommand::new(operator, vec![]))
+}
+
+// Comparisons of the same attribute in an AND that no value satisfies at once,
+// like `x gt 5 AND x lt 3` or a null check next to a comparison. Only numbers
+// and dates are considered, text equality depends on the collation of the database.
+fn has_contradiction(commands: &[Command]) -> bool {
+    let mut commands_by_attribute: HashMap<&str, Vec<&SingleCommand>> = HashMap::new();
+
+    for command in commands {
+        if let Command::SingleCommand(single_command) = command {
+            commands_by_attribute
+                .entry(single_command.attribute.as_str())
+                .or_default()
+                .push(single_command);
+        }
+    }
+
+    commands_by_attribute.values().any(|commands| {
+        let is_null = commands.iter().any(|c| c.operator == Operator::IsNull);
+        let compares_value = commands.iter().any(|c| c.operator != Operator::IsNull);
+
+        (is_null && compares_value) || bounds_are_empty(commands)
+    })
+}
+
+fn bounds_are_empty(commands: &[&SingleCommand]) -> bool {
+    // the value of each bound and whether the bound is included
+    let mut lower: Option<(&Value, bool)> = None;
+    let mut upper: Option<(&Value, bool)> = None;
+    let mut excluded: Vec<&Value> = Vec::new();
+
+    for command in commands {
+        let value = &command.value;
+
+        let (command_lower, command_upper) = match command.operator {
+            Operator::EqualTo => (Some((value, true)), Some((value, true))),
+            Operator::GreaterThan => (Some((value, false)), None),
+            Operator::GreaterThanOrEqualTo => (Some((value, true)), None),
+            Operator::LessThan => (None, Some((value, false))),
+            Operator::LessThanOrEqualTo => (None, Some((value, true))),
+            Operator::Between if value.list.len() == 2 => {
+                (Some((&value.list[0], true)), Some((&value.list[1], true)))
+            }
+            Operator::NotEqualTo => {
+                excluded.push(value);
+                (None, None)
+            }
+            _ => (None, None),
+        };
+
+        if let Some(bound) = command_lower {
+            match lower.map(|l| compare_bounds(bound, l, Ordering::Greater)) {
+                Some(None) => return false,
+                Some(Some(false)) => {}
+                _ => lower = Some(bound),
+            }
+        }
+
+        if let Some(bound) = command_upper {
+            match upper.map(|u| compare_bounds(bound, u, Ordering::Less)) {
+                Some(None) => return false,
+                Some(Some(false)) => {}
+                _ => upper = Some(bound),
+            }
+        }
+    }
+
+    let (Some((lower_value, lower_included)), Some((upper_value, upper_included))) = (lower, upper) else {
+        return false;
+    };
+
+    match compare_values(lower_value, upper_value) {
+        Some(Ordering::Greater) => true,
+        Some(Ordering::Equal) => {
+            !lower_included
+                || !upper_included
+                || excluded
+                    .iter()
+                    .any(|v| compare_values(v, lower_value) == Some(Ordering::Equal))
+        }
+        _ => false,
+    }
+}
+
+// Whether the bound is tighter than the current one, None when they can't be compared.
+fn compare_bounds(bound: (&Value, bool), current: (&Value, bool), tighter: Ordering) -> Option<bool> {
+    let ordering = compare_values(bound.0, current.0)?;
+
+    Some(ordering == tighter || (ordering == Ordering::Equal && !bound.1))
+}
+
+fn compare_values(value: &Value, other_value: &Value) -> Option<Ordering> {
+    if value.data_type != other_value.data_type {
+        return None;
+    }
+
+    match value.data_type {
+        DataType::Integer => Some(value.value.parse::<i64>().ok()?.cmp(&other_value.value.parse::<i64>().ok()?)),
+        DataType::Float => value.value.parse::<f64>().ok()?.partial_cmp(&other_value.value.parse::<f64>().ok()?),
+        // dates are written as YYYY-MM-DD, so they sort as text
+        DataType::Date => Some(value.value.cmp(&other_value.value)),
+        _ => None,
+    }
+}
+
+// Moves every NOT down to the single commands, swapping AND and OR on the way.
+fn negate_command(command: Command, negated: bool) -> Command {
+    match command {
+        Command::CompositeCommand(mut composite_command) => {
+            let operator = match (&composite_command.logical_operator, negated) {
+                (LogicalOperator::Not, _) if composite_command.commands.len() == 1 => {
+                    return negate_command(composite_command.commands.remove(0), !negated)
+                }
+                (LogicalOperator::And, true) => LogicalOperator::Or,
+                (LogicalOperator::Or, true) => LogicalOperator::And,
+                (operator, _) => operator.clone(),
+            };
+
+            let commands = composite_command
+                .commands
+                .into_iter()
+                .map(|c| negate_command(c, negated))
+                .collect();
+
+            Command::CompositeCommand(CompositeCommand::new(operator, commands))
+        }
+        command => match negated {
+            true => Command::CompositeCommand(CompositeCommand::new(LogicalOperator::Not, vec![command])),
+            false => command,
+        },
+    }
+}
+
+// The groups of the normal form, each one holding the commands joined by the inner operator.
+fn normal_form_groups(
+    command: &Command,
+    outer_operator: &LogicalOperator,
+    inner_operator: &LogicalOperator,
+) -> Result<Vec<Vec<Command>>, Error> {
+    let Command::CompositeCommand(composite_command) = command else {
+        return Ok(vec![vec![command.clone()]]);
+    };
+
+    let operator = &composite_command.logical_operator;
+
+    let groups = if operator == outer_operator {
+        let mut groups = Vec::new();
+        for nested_command in composite_command.commands.iter() {
+            groups.extend(normal_form_groups(nested_command, outer_operator, inner_operator)?);
+        }
+        groups
+    } else if operator == inner_operator {
+        let mut groups: Vec<Vec<Command>> = vec![vec![]];
+        for nested_command in composite_command.commands.iter() {
+            let nested_groups = normal_form_groups(nested_command, outer_operator, inner_operator)?;
+
+            if groups.len() * nested_groups.len() > MAX_NORMAL_FORM_GROUPS {
+                return Err(anyhow!(
+                    "The filters have more than {} groups in normal form",
+                    MAX_NORMAL_FORM_GROUPS
+                ));
+            }
+
+            groups = groups
+                .iter()
+                .flat_map(|group| {
+                    nested_groups.iter().map(move |nested_group| {
+                        group.iter().chain(nested_group.iter()).cloned().collect()
+                    })
+                })
+                .collect();
+        }
+        groups
+    } else {
+        // negated single commands
+        vec![vec![command.clone()]]
+    };
+
+    Ok(groups)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn single_command(attribute: &str, operator: Operator, value: &str) -> Command {
+        Command::SingleCommand(SingleCommand::new(
+            attribute.into(),
+            operator,
+            Value::new(value.into(), DataType::Integer),
+        ))
+    }
+
+    fn composite_command(operator: LogicalOperator, commands: Vec<Command>) -> Command {
+        Command::CompositeCommand(CompositeCommand::new(operator, commands))
+    }
+
+    #[test]
+    fn test_flatten_and_deduplicate() -> Result<(), Error> {
+        let a = single_command("movies.movie.runtime", Operator::GreaterThan, "90");
+        let b = single_command("movies.movie.budget", Operator::GreaterThan, "1000");
+        let c = single_command("movies.movie.revenue", Operator::LessThan, "5000");
+
+        let command = composite_command(
+            LogicalOperator::And,
+            vec![
+                a.clone(),
+                composite_command(LogicalOperator::And, vec![b.clone(), a.clone()]),
+                composite_command(LogicalOperator::Or, vec![c.clone(), c.clone()]),
+                composite_command(
+                    LogicalOperator::Not,
+                    vec![composite_command(LogicalOperator::Not, vec![b.clone()])],
+                ),
+            ],
+        );
+
+        assert_eq!(
+            normalize_command(command, None)?,
+            composite_command(LogicalOperator::And, vec![a, b, c])
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fold_contradictions() -> Result<(), Error> {
+        let runtime = "movies.movie.runtime";
+
+        let contradictions = vec![
+            vec![
+                single_command(runtime, Operator::GreaterThan, "5"),
+                single_command(runtime, Operator::LessThan, "3"),
+            ],
+            vec![
+                single_command(runtime, Operator::GreaterThanOrEqualTo, "5"),
+                single_command(runtime, Operator::LessThan, "5"),
+            ],
+            vec![
+                single_command(runtime, Operator::EqualTo, "5"),
+                single_command(runtime, Operator::EqualTo, "6"),
+            ],
+            vec![
+                single_command(runtime, Operator::EqualTo, "5"),
+                single_command(runtime, Operator::NotEqualTo, "5"),
+            ],
+            vec![
+                single_command(runtime, Operator::IsNull, ""),
+                single_command(runtime, Operator::GreaterThan, "5"),
+            ],
+        ];
+
+        for commands in contradictions {
+            let command = composite_command(LogicalOperator::And, commands);
+            assert_eq!(normalize_command(command, None)?, constant_command(false));
+        }
+
+        let title = single_command("movies.movie.title", Operator::EqualTo, "2046");
+        let command = composite_command(
+            LogicalOperator::Or,
+            vec![
+                title.clone(),
+                composite_command(
+                    LogicalOperator::And,
+                    vec![
+                        single_command(runtime, Operator::GreaterThan, "5"),
+                        single_command(runtime, Operator::LessThan, "3"),
+                    ],
+                ),
+            ],
+        );
+        assert_eq!(normalize_command(command, None)?, title);
+
+        let satisfiable = composite_command(
+            LogicalOperator::And,
+            vec![
+                single_command(runtime, Operator::GreaterThanOrEqualTo, "5"),
+                single_command(runtime, Operator::LessThanOrEqualTo, "5"),
+                single_command("movies.movie.budget", Operator::LessThan, "3"),
+            ],
+        );
+        assert_eq!(normalize_command(satisfiable.clone(), None)?, satisfiable);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_rewrite_to_normal_forms() -> Result<(), Error> {
+        let a = single_command("movies.movie.runtime", Operator::GreaterThan, "90");
+        let b = single_command("movies.movie.budget", Operator::GreaterThan, "1000");
+        let c = single_command("movies.movie.revenue", Operator::LessThan, "5000");
+        let not = |command: &Command| composite_command(LogicalOperator::Not, vec![command.clone()]);
+
+        // a AND NOT (b AND c)
+        let command = composite_command(
+            LogicalOperator::And,
+            vec![
+                a.clone(),
+                composite_command(
+                    LogicalOperator::Not,
+                    vec![composite_command(LogicalOperator::And, vec![b.clone(), c.clone()])],
+                ),
+            ],
+        );
+
+        assert_eq!(
+            normalize_command(command.clone(), Some(&NormalForm::Dnf))?,
+            composite_command(
+                LogicalOperator::Or,
+                vec![
+                    composite_command(LogicalOperator::And, vec![a.clone(), not(&b)]),
+                    composite_command(LogicalOperator::And, vec![a.clone(), not(&c)]),
+                ]
+            )
+        );
+
+        assert_eq!(
+            normalize_command(command, Some(&NormalForm::Cnf))?,
+            composite_command(
+                LogicalOperator::And,
+                vec![a, composite_command(LogicalOperator::Or, vec![not(&b), not(&c)])]
+            )
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_limit_normal_form_size() -> Result<(), Error> {
+        let command = composite_command(
+            LogicalOperator::And,
+            (0..10)
+                .map(|i| {
+                    composite_command(
+                        LogicalOperator::Or,
+                        vec![
+                            single_command("movies.movie.runtime", Operator::EqualTo, &i.to_string()),
+                            single_command("movies.movie.budget", Operator::EqualTo, &i.to_string()),
+                        ],
+                    )
+                })
+                .collect(),
+        );
+
+        assert!(normalize_command(command, Some(&NormalForm::Dnf)).is_err());
+
+        Ok(())
+    }
+}
@@ -2,7 +2,7 @@ use serde::{Deserialize, Serialize};
 
 use strum_macros::{Display, EnumIter};
 
-#[derive(PartialEq, Debug)]
+#[derive(PartialEq, Debug, Clone)]
 pub struct SingleCommand {
     pub attribute: String,
     pub operator: Operator,
@@ -13,6 +13,9 @@ pub struct SingleCommand {
 pub struct Value {
     pub value: String,
     pub data_type: DataType,
+    // literals of a List value, each one with its own type,
+    // or the lower and upper bounds of a Range value
+    pub list: Vec<Value>,
 }
 
 #[derive(Serialize, Deserialize, PartialEq,
@@ -24,9 +27,24 @@ pub enum Operator {
     GreaterThanOrEqualTo,
     LessThanOrEqualTo,
     NotEqualTo,
+    Contains,
+    StartsWith,
+    EndsWith,
+    Like,
+    EqualToIgnoreCase,
+    NotEqualToIgnoreCase,
+    ContainsIgnoreCase,
+    StartsWithIgnoreCase,
+    EndsWithIgnoreCase,
+    LikeIgnoreCase,
+    In,
+    NotIn,
+    Between,
+    IsNull,
+    IsNotNull,
 }
 
-#[derive(Serialize, Deserialize, PartialEq,
+#[derive(Serialize, Deserialize, PartialEq, Eq, Hash,
     Debug, Clone, EnumIter, Display)]
 pub enum DataType {
     Integer,
@@ -34,6 +52,9 @@ pub enum DataType {
     Attribute,
     Float,
     Date,
+    List,
+    Range,
+    Null,
 }
 
 impl SingleCommand {
@@ -46,8 +67,93 @@ impl SingleCommand {
     }
 }
 
+impl Operator {
+    // operators whose value is matched as a pattern against text attributes
+    pub fn is_pattern(&self) -> bool {
+        matches!(
+            self.case_sensitive(),
+            Operator::Contains | Operator::StartsWith | Operator::EndsWith | Operator::Like
+        )
+    }
+
+    // operators comparing the order of an attribute and a value
+    pub fn is_order_comparison(&self) -> bool {
+        matches!(
+            self,
+            Operator::GreaterThan
+                | Operator::LessThan
+                | Operator::GreaterThanOrEqualTo
+                | Operator::LessThanOrEqualTo
+        )
+    }
+
+    // operators comparing an attribute against a List value
+    pub fn is_set_membership(&self) -> bool {
+        matches!(self, Operator::In | Operator::NotIn)
+    }
+
+    // operators comparing an attribute against a Range value
+    pub fn is_range(&self) -> bool {
+        matches!(self, Operator::Between)
+    }
+
+    // operators checking for missing data, they take no value
+    pub fn is_null_check(&self) -> bool {
+        matches!(self, Operator::IsNull | Operator::IsNotNull)
+    }
+
+    pub fn is_case_insensitive(&self) -> bool {
+        self.case_sensitive() != *self
+    }
+
+    // the operator that compares the same way but respecting case
+    pub fn case_sensitive(&self) -> Operator {
+        match self {
+            Operator::EqualToIgnoreCase => Operator::EqualTo,
+            Operator::NotEqualToIgnoreCase => Operator::NotEqualTo,
+            Operator::ContainsIgnoreCase => Operator::Contains,
+            Operator::StartsWithIgnoreCase => Operator::StartsWith,
+            Operator::EndsWithIgnoreCase => Operator::EndsWith,
+            Operator::LikeIgnoreCase => Operator::Like,
+            operator => operator.clone(),
+        }
+    }
+}
+
+impl DataType {
+    // data types whose values have a meaningful order, so they can bound a range
+    pub fn is_ordered(&self) -> bool {
+        matches!(self, DataType::Integer | DataType::Float | DataType::Date)
+    }
+}
+
 impl Value {
     pub fn new(value: String, data_type: DataType) -> Self {
-        Self { value, data_type }
+        Self {
+            value,
+            data_type,
+            list: vec![],
+        }
+    }
+
+    pub fn new_list(list: Vec<Value>) -> Self {
+        Self {
+            value: String::new(),
+            data_type: DataType::List,
+            list,
+        }
+    }
+
+    // the absent value of null check operators
+    pub fn empty() -> Self {
+        Self::new(String::new(), DataType::Null)
+    }
+
+    pub fn new_range(lower: Value, upper: Value) -> Self {
+        Self {
+            value: String::new(),
+            data_type: DataType::Range,
+            list: vec![lower, upper],
+        }
     }
 }
@@ -1,4 +1,5 @@
 #[cfg(test)]
+#[allow(clippy::module_inception, clippy::vec_init_then_push)]
 pub mod tests {
 
     use crate::query_representation::intermediary::{get_command_attributes, Command};
@@ -262,6 +263,25 @@ pub mod tests {
             ]
         );
 
+        // Testing attribute as a bound of a range
+        let range_command_with_attribute = Command::SingleCommand(
+            SingleCommand::new(
+                "movies.movie.runtime".to_string(),
+                Operator::Between,
+                Value::new_range(
+                    Value::new("1".to_string(), DataType::Integer),
+                    Value::new("movies.movie_cast.cast_order".to_string(), DataType::Attribute),
+                )
+            )
+        );
+        assert_eq!(
+            get_command_attributes(&range_command_with_attribute),
+            vec![
+                "movies.movie.runtime".to_string(),
+                "movies.movie_cast.cast_order".to_string()
+            ]
+        );
+
 
         Ok(())
     }
@@ -0,0 +1,31 @@
+use serde::Serialize;
+
+use thiserror::Error;
+
+use crate::query_representation::intermediary::single_command::{DataType, Operator};
+
+#[derive(Error, Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TypeError {
+    #[error("Operator {operator} is not available for attribute {attribute} of type {data_type}")]
+    UnsupportedOperator {
+        attribute: String,
+        data_type: DataType,
+        operator: Operator,
+    },
+
+    #[error("Value {value} is not compatible with attribute {attribute} of type {data_type}")]
+    IncompatibleValue {
+        attribute: String,
+        data_type: DataType,
+        value: String,
+    },
+
+    #[error("Attribute {attribute} of type {data_type} can't be compared to attribute {other_attribute} of type {other_data_type}")]
+    IncomparableAttributes {
+        attribute: String,
+        data_type: DataType,
+        other_attribute: String,
+        other_data_type: DataType,
+    },
+}
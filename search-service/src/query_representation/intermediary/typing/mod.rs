@@ -0,0 +1,461 @@
+/*
+    Module responsible for typing the literals of a command. The
+    initial representation only tells literals and attributes apart,
+    the type of each literal is taken from the declared type of the
+    attribute it is compared to. Operators and attribute comparisons
+    are checked against the same types, so a command that would fail
+    in the database is rejected with every problem found.
+*/
+
+pub mod errors;
+
+use std::collections::HashMap;
+
+use crate::query_representation::intermediary::single_command::{
+    DataType, Operator, SingleCommand, Value,
+};
+use crate::query_representation::intermediary::Command;
+
+use self::errors::TypeError;
+
+// Data type of every attribute of the database, by its full name (schema.table.attribute).
+pub type AttributeTypes = HashMap<String, DataType>;
+
+// Types the literals of the command, returning every problem found when it can't be run.
+pub fn resolve_command_types(
+    command: &mut Command,
+    attribute_types: &AttributeTypes,
+) -> Result<(), Vec<TypeError>> {
+    let mut errors: Vec<TypeError> = Vec::new();
+
+    resolve_types(command, attribute_types, &mut errors);
+
+    match errors.is_empty() {
+        true => Ok(()),
+        false => Err(errors),
+    }
+}
+
+fn resolve_types(command: &mut Command, attribute_types: &AttributeTypes, errors: &mut Vec<TypeError>) {
+    match command {
+        Command::CompositeCommand(composite_command) => {
+            for command in composite_command.commands.iter_mut() {
+                resolve_types(command, attribute_types, errors);
+            }
+        }
+        Command::SingleCommand(single_command) => {
+            resolve_single_command_types(single_command, attribute_types, errors)
+        }
+    }
+}
+
+fn resolve_single_command_types(
+    single_command: &mut SingleCommand,
+    attribute_types: &AttributeTypes,
+    errors: &mut Vec<TypeError>,
+) {
+    // unknown attributes are reported by the search manager
+    let Some(attribute_type) = attribute_types.get(&single_command.attribute) else {
+        return;
+    };
+
+    if !operator_accepts_type(&single_command.operator, attribute_type) {
+        errors.push(TypeError::UnsupportedOperator {
+            attribute: single_command.attribute.to_owned(),
+            data_type: attribute_type.clone(),
+            operator: single_command.operator.clone(),
+        });
+        return;
+    }
+
+    // patterns are matched against the text of the attribute
+    if single_command.operator.is_pattern() {
+        return;
+    }
+
+    let values = match single_command.value.data_type {
+        DataType::List | DataType::Range => single_command.value.list.iter_mut().collect(),
+        _ => vec![&mut single_command.value],
+    };
+
+    for value in values {
+        resolve_value_type(value, &single_command.attribute, attribute_type, attribute_types, errors);
+    }
+}
+
+fn resolve_value_type(
+    value: &mut Value,
+    attribute: &str,
+    attribute_type: &DataType,
+    attribute_types: &AttributeTypes,
+    errors: &mut Vec<TypeError>,
+) {
+    if let DataType::Null = value.data_type {
+        return;
+    }
+
+    // values shaped like attributes that don't name one are plain literals
+    if let DataType::Attribute = value.data_type {
+        if let Some(other_type) = attribute_types.get(&value.value) {
+            if !types_are_comparable(attribute_type, other_type) {
+                errors.push(TypeError::IncomparableAttributes {
+                    attribute: attribute.to_owned(),
+                    data_type: attribute_type.clone(),
+                    other_attribute: value.value.to_owned(),
+                    other_data_type: other_type.clone(),
+                });
+            }
+            return;
+        }
+    }
+
+    if !literal_has_type(&value.value, attribute_type) {
+        errors.push(TypeError::IncompatibleValue {
+            attribute: attribute.to_owned(),
+            data_type: attribute_type.clone(),
+            value: value.value.to_owned(),
+        });
+        return;
+    }
+
+    value.data_type = attribute_type.clone();
+}
+
+// Patterns and case insensitive comparisons work on text, order comparisons and
+// ranges need ordered values.
+pub fn operator_accepts_type(operator: &Operator, data_type: &DataType) -> bool {
+    if operator.is_pattern() || operator.is_case_insensitive() {
+        return matches!(data_type, DataType::String);
+    }
+
+    if operator.is_order_comparison() || operator.is_range() {
+        return data_type.is_ordered();
+    }
+
+    true
+}
+
+// Numbers compare with each other whatever their precision, other types only with themselves.
+fn types_are_comparable(data_type: &DataType, other_data_type: &DataType) -> bool {
+    let is_number = |data_type: &DataType| matches!(data_type, DataType::Integer | DataType::Float);
+
+    data_type == other_data_type || (is_number(data_type) && is_number(other_data_type))
+}
+
+pub fn literal_has_type(literal: &str, data_type: &DataType) -> bool {
+    match data_type {
+        DataType::Integer => literal.parse::<i64>().is_ok(),
+        DataType::Float => literal.parse::<f64>().is_ok_and(|f| f.is_finite()),
+        DataType::Date => literal_is_date(literal),
+        DataType::String => true,
+        _ => false,
+    }
+}
+
+// Dates are written as YYYY-MM-DD and must exist in the calendar.
+fn literal_is_date(literal: &str) -> bool {
+    let parts: Vec<&str> = literal.split('-').collect();
+
+    let [year, month, day] = parts.as_slice() else {
+        return false;
+    };
+
+    let is_number = |part: &str, len: usize| {
+        part.len() == len && part.chars().all(|c| c.is_ascii_digit())
+    };
+
+    if !is_number(year, 4) || !is_number(month, 2) || !is_number(day, 2) {
+        return false;
+    }
+
+    let year: u32 = year.parse().unwrap_or(0);
+    let month: u32 = month.parse().unwrap_or(0);
+    let day: u32 = day.parse().unwrap_or(0);
+
+    let is_leap_year = (year.is_multiple_of(4) && !year.is_multiple_of(100)) || year.is_multiple_of(400);
+    let days_in_month = match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if is_leap_year => 29,
+        2 => 28,
+        _ => return false,
+    };
+
+    (1..=days_in_month).contains(&day)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    use anyhow::{anyhow, Error};
+
+    use crate::query_representation::intermediary::composite_command::{
+        CompositeCommand, LogicalOperator,
+    };
+
+    fn aux_get_attribute_types() -> AttributeTypes {
+        let mut attribute_types = AttributeTypes::new();
+        attribute_types.insert("movies.movie.title".into(), DataType::String);
+        attribute_types.insert("movies.movie.homepage".into(), DataType::String);
+        attribute_types.insert("movies.movie.runtime".into(), DataType::Integer);
+        attribute_types.insert("movies.movie.budget".into(), DataType::Integer);
+        attribute_types.insert("movies.movie.popularity".into(), DataType::Float);
+        attribute_types.insert("movies.movie.release_date".into(), DataType::Date);
+        attribute_types
+    }
+
+    fn aux_resolve(attribute: &str, operator: Operator, value: Value) -> Result<Value, Error> {
+        let mut command = Command::SingleCommand(SingleCommand::new(attribute.into(), operator, value));
+
+        resolve_command_types(&mut command, &aux_get_attribute_types())
+            .map_err(|errors| anyhow!("{:?}", errors))?;
+
+        match command {
+            Command::SingleCommand(single_command) => Ok(single_command.value),
+            Command::CompositeCommand(_) => Err(anyhow!("Expected a single command")),
+        }
+    }
+
+    #[test]
+    fn test_resolve_literal_types() -> Result<(), Error> {
+        let cases = vec![
+            ("movies.movie.title", "1917", DataType::String),
+            ("movies.movie.runtime", "120", DataType::Integer),
+            ("movies.movie.popularity", "12.5", DataType::Float),
+            ("movies.movie.popularity", "12", DataType::Float),
+            ("movies.movie.release_date", "2000-01-01", DataType::Date),
+            ("movies.movie.release_date", "2000-02-29", DataType::Date),
+        ];
+
+        for (attribute, literal, data_type) in cases {
+            let value = aux_resolve(
+                attribute,
+                Operator::EqualTo,
+                Value::new(literal.into(), DataType::String),
+            )?;
+            assert_eq!(value, Value::new(literal.into(), data_type));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_resolve_attribute_like_values() -> Result<(), Error> {
+        let value = aux_resolve(
+            "movies.movie.homepage",
+            Operator::EqualTo,
+            Value::new("www.x.com".into(), DataType::Attribute),
+        )?;
+        assert_eq!(value, Value::new("www.x.com".into(), DataType::String));
+
+        let value = aux_resolve(
+            "movies.movie.runtime",
+            Operator::GreaterThan,
+            Value::new("movies.movie.budget".into(), DataType::Attribute),
+        )?;
+        assert_eq!(value, Value::new("movies.movie.budget".into(), DataType::Attribute));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_resolve_nested_values() -> Result<(), Error> {
+        let value = aux_resolve(
+            "movies.movie.runtime",
+            Operator::In,
+            Value::new_list(vec![
+                Value::new("90".into(), DataType::String),
+                Value::new("120".into(), DataType::String),
+            ]),
+        )?;
+        assert_eq!(
+            value,
+            Value::new_list(vec![
+                Value::new("90".into(), DataType::Integer),
+                Value::new("120".into(), DataType::Integer),
+            ])
+        );
+
+        let value = aux_resolve(
+            "movies.movie.release_date",
+            Operator::Between,
+            Value::new_range(
+                Value::new("2000-01-01".into(), DataType::String),
+                Value::new("2009-12-31".into(), DataType::String),
+            ),
+        )?;
+        assert_eq!(
+            value,
+            Value::new_range(
+                Value::new("2000-01-01".into(), DataType::Date),
+                Value::new("2009-12-31".into(), DataType::Date),
+            )
+        );
+
+        let mut command = Command::CompositeCommand(CompositeCommand::new(
+            LogicalOperator::Not,
+            vec![Command::SingleCommand(SingleCommand::new(
+                "movies.movie.runtime".into(),
+                Operator::EqualTo,
+                Value::new("120".into(), DataType::String),
+            ))],
+        ));
+        resolve_command_types(&mut command, &aux_get_attribute_types())
+            .map_err(|errors| anyhow!("{:?}", errors))?;
+        assert_eq!(
+            command,
+            Command::CompositeCommand(CompositeCommand::new(
+                LogicalOperator::Not,
+                vec![Command::SingleCommand(SingleCommand::new(
+                    "movies.movie.runtime".into(),
+                    Operator::EqualTo,
+                    Value::new("120".into(), DataType::Integer),
+                ))],
+            ))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_resolve_untyped_values() -> Result<(), Error> {
+        let value = aux_resolve(
+            "movies.movie.title",
+            Operator::Contains,
+            Value::new("12".into(), DataType::String),
+        )?;
+        assert_eq!(value, Value::new("12".into(), DataType::String));
+
+        let value = aux_resolve("movies.movie.runtime", Operator::IsNull, Value::empty())?;
+        assert_eq!(value, Value::empty());
+
+        let value = aux_resolve(
+            "movies.movie.unknown",
+            Operator::EqualTo,
+            Value::new("12".into(), DataType::String),
+        )?;
+        assert_eq!(value, Value::new("12".into(), DataType::String));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_reject_incompatible_literals() -> Result<(), Error> {
+        let cases = vec![
+            ("movies.movie.runtime", "two hours"),
+            ("movies.movie.runtime", "12.5"),
+            ("movies.movie.popularity", "popular"),
+            ("movies.movie.release_date", "01-01-2000"),
+            ("movies.movie.release_date", "2000-13-01"),
+            ("movies.movie.release_date", "2000-02-31"),
+            ("movies.movie.release_date", "1900-02-29"),
+            ("movies.movie.release_date", "2001-04-31"),
+            ("movies.movie.runtime", "movies.movie.unknown"),
+        ];
+
+        for (attribute, literal) in cases {
+            let value = Value::new(literal.into(), DataType::String);
+            assert!(aux_resolve(attribute, Operator::EqualTo, value).is_err());
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_reject_unsupported_operators() -> Result<(), Error> {
+        let cases = vec![
+            ("movies.movie.runtime", Operator::Contains),
+            ("movies.movie.release_date", Operator::StartsWithIgnoreCase),
+            ("movies.movie.popularity", Operator::EqualToIgnoreCase),
+            ("movies.movie.title", Operator::Between),
+            ("movies.movie.title", Operator::GreaterThan),
+        ];
+
+        for (attribute, operator) in cases {
+            let value = Value::new("1".into(), DataType::String);
+            assert!(aux_resolve(attribute, operator, value).is_err());
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_compare_attributes() -> Result<(), Error> {
+        let cases = vec![
+            ("movies.movie.runtime", "movies.movie.popularity", true),
+            ("movies.movie.title", "movies.movie.homepage", true),
+            ("movies.movie.title", "movies.movie.budget", false),
+            ("movies.movie.release_date", "movies.movie.runtime", false),
+        ];
+
+        for (attribute, other_attribute, comparable) in cases {
+            let value = Value::new(other_attribute.into(), DataType::Attribute);
+            assert_eq!(aux_resolve(attribute, Operator::EqualTo, value).is_ok(), comparable);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_report_every_type_error() -> Result<(), Error> {
+        let single_command = |attribute: &str, operator: Operator, value: Value| {
+            Command::SingleCommand(SingleCommand::new(attribute.into(), operator, value))
+        };
+
+        let mut command = Command::CompositeCommand(CompositeCommand::new(
+            LogicalOperator::And,
+            vec![
+                single_command(
+                    "movies.movie.title",
+                    Operator::EqualTo,
+                    Value::new("movies.movie.budget".into(), DataType::Attribute),
+                ),
+                Command::CompositeCommand(CompositeCommand::new(
+                    LogicalOperator::Or,
+                    vec![
+                        single_command(
+                            "movies.movie.release_date",
+                            Operator::GreaterThanOrEqualTo,
+                            Value::new("hello".into(), DataType::String),
+                        ),
+                        single_command(
+                            "movies.movie.runtime",
+                            Operator::Contains,
+                            Value::new("1".into(), DataType::String),
+                        ),
+                    ],
+                )),
+            ],
+        ));
+
+        let errors = resolve_command_types(&mut command, &aux_get_attribute_types())
+            .err()
+            .ok_or(anyhow!("Expected type errors"))?;
+
+        assert_eq!(
+            errors,
+            vec![
+                TypeError::IncomparableAttributes {
+                    attribute: "movies.movie.title".into(),
+                    data_type: DataType::String,
+                    other_attribute: "movies.movie.budget".into(),
+                    other_data_type: DataType::Integer,
+                },
+                TypeError::IncompatibleValue {
+                    attribute: "movies.movie.release_date".into(),
+                    data_type: DataType::Date,
+                    value: "hello".into(),
+                },
+                TypeError::UnsupportedOperator {
+                    attribute: "movies.movie.runtime".into(),
+                    data_type: DataType::Integer,
+                    operator: Operator::Contains,
+                },
+            ]
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,259 @@
+/*
+    SQL written differently by each database: how names are quoted, how values are
+    cast and bound, how text is compared ignoring case, where missing values are
+    sorted and how a page of rows is taken. The rest of the query is standard SQL.
+*/
+
+use anyhow::Error;
+
+use crate::query_representation::intermediary::single_command::Operator;
+
+use super::{translate_direction, translate_operator, Direction, NullsOrder, Page, QueryParameter};
+
+
+pub trait SqlDialect: Send + Sync {
+    // a single name (schema, table, attribute or alias) read exactly as written
+    fn quote_identifier(&self, identifier: &str) -> String;
+
+    // an expression whose value the storage reads as text, as every projected value is
+    fn cast_to_text(&self, expression: &str) -> String;
+
+    // placeholder of a parameter of the query, positions start at 1
+    fn placeholder(&self, parameter: &QueryParameter, position: usize) -> String;
+
+    // comparison of an attribute with a value ignoring case, and also accents with unaccent
+    fn compare_ignoring_case(
+        &self,
+        attribute: &str,
+        operator: &Operator,
+        value: &str,
+        unaccent: bool,
+    ) -> Result<String, Error>;
+
+    // a sort key placing the missing values first or last
+    fn sort_key(&self, expression: &str, direction: &Direction, nulls: &NullsOrder) -> String;
+
+    // whether missing values are sorted after every other one in ascending order
+    fn nulls_are_greatest(&self) -> bool;
+
+    // schema.table.attribute with each of its parts quoted
+    fn quote_name(&self, name: &str) -> String {
+        name.split('.')
+            .map(|part| self.quote_identifier(part))
+            .collect::<Vec<String>>()
+            .join(".")
+    }
+
+    // a string written in the query itself rather than bound
+    fn string_literal(&self, value: &str) -> String {
+        format!("'{}'", value.replace('\'', "''"))
+    }
+
+    fn limit(&self, page: &Page) -> String {
+        format!("LIMIT {} OFFSET {}", page.limit, page.offset)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PostgresDialect;
+
+#[derive(Debug, Clone, Default)]
+pub struct MySQLDialect {
+    // whether the server has the utf8mb4_0900 collations, added by MySQL 8
+    pub unicode_9_collations: bool,
+}
+
+impl MySQLDialect {
+    // version as told by SELECT VERSION(), e.g. 8.0.36 or 10.6.12-MariaDB
+    pub fn for_server_version(version: &str) -> Self {
+        let major_version = version
+            .split('.')
+            .next()
+            .and_then(|major| major.parse::<u32>().ok())
+            .unwrap_or(0);
+
+        Self {
+            unicode_9_collations: major_version >= 8 && !version.contains("MariaDB"),
+        }
+    }
+}
+
+impl SqlDialect for PostgresDialect {
+    fn quote_identifier(&self, identifier: &str) -> String {
+        format!("\"{}\"", identifier.replace('"', "\"\""))
+    }
+
+    fn cast_to_text(&self, expression: &str) -> String {
+        format!("{}::TEXT", expression)
+    }
+
+    // placeholders are cast so their type doesn't depend on the attribute they are compared to
+    fn placeholder(&self, parameter: &QueryParameter, position: usize) -> String {
+        match parameter {
+            QueryParameter::Integer(_) => format!("${}::BIGINT", position),
+            QueryParameter::Float(_) => format!("${}::DOUBLE PRECISION", position),
+            QueryParameter::String(_) => format!("${}::TEXT", position),
+            QueryParameter::Date(_) => format!("${}::TEXT::DATE", position),
+        }
+    }
+
+    // both sides are lowered (or compared with ILIKE for patterns), also removing
+    // accents when the unaccent extension is installed
+    fn compare_ignoring_case(
+        &self,
+        attribute: &str,
+        operator: &Operator,
+        value: &str,
+        unaccent: bool,
+    ) -> Result<String, Error> {
+        let fold = |sql: &str| match unaccent {
+            true => format!("unaccent({})", sql),
+            false => sql.to_owned(),
+        };
+
+        let comparison = if operator.is_pattern() {
+            format!("{} ILIKE {}", fold(attribute), fold(value))
+        } else {
+            format!(
+                "LOWER({}){}LOWER({})",
+                fold(attribute),
+                translate_operator(operator)?,
+                fold(value)
+            )
+        };
+
+        Ok(comparison)
+    }
+
+    fn sort_key(&self, expression: &str, direction: &Direction, nulls: &NullsOrder) -> String {
+        let nulls = match nulls {
+            NullsOrder::First => "FIRST",
+            NullsOrder::Last => "LAST",
+        };
+        format!("{} {} NULLS {}", expression, translate_direction(direction), nulls)
+    }
+
+    fn nulls_are_greatest(&self) -> bool {
+        true
+    }
+}
+
+impl SqlDialect for MySQLDialect {
+    fn quote_identifier(&self, identifier: &str) -> String {
+        format!("`{}`", identifier.replace('`', "``"))
+    }
+
+    // values are already read as text by the storage
+    fn cast_to_text(&self, expression: &str) -> String {
+        expression.to_owned()
+    }
+
+    fn placeholder(&self, _parameter: &QueryParameter, _position: usize) -> String {
+        "?".to_string()
+    }
+
+    // backslashes escape characters in MySQL strings, unless told otherwise
+    fn string_literal(&self, value: &str) -> String {
+        format!("'{}'", value.replace('\\', "\\\\").replace('\'', "''"))
+    }
+
+    // compared under a case insensitive collation, converting the attribute first so any
+    // charset is accepted. Before MySQL 8 every case insensitive collation also ignores
+    // accents, so both sides are lowered and compared byte by byte instead.
+    fn compare_ignoring_case(
+        &self,
+        attribute: &str,
+        operator: &Operator,
+        value: &str,
+        unaccent: bool,
+    ) -> Result<String, Error> {
+        let attribute = format!("CONVERT({} USING utf8mb4)", attribute);
+        let operator = translate_operator(operator)?;
+
+        let comparison = match (self.unicode_9_collations, unaccent) {
+            (true, true) => format!("{} COLLATE utf8mb4_0900_ai_ci{}{}", attribute, operator, value),
+            (true, false) => format!("{} COLLATE utf8mb4_0900_as_ci{}{}", attribute, operator, value),
+            (false, true) => format!("{} COLLATE utf8mb4_unicode_ci{}{}", attribute, operator, value),
+            (false, false) => format!(
+                "LOWER({}) COLLATE utf8mb4_bin{}LOWER({})",
+                attribute, operator, value
+            ),
+        };
+
+        Ok(comparison)
+    }
+
+    // there is no NULLS FIRST/LAST, missing values are placed by sorting on IS NULL first
+    fn sort_key(&self, expression: &str, direction: &Direction, nulls: &NullsOrder) -> String {
+        let nulls = match nulls {
+            NullsOrder::First => "DESC",
+            NullsOrder::Last => "ASC",
+        };
+        format!(
+            "{} IS NULL {}, {} {}",
+            expression,
+            nulls,
+            expression,
+            translate_direction(direction)
+        )
+    }
+
+    fn nulls_are_greatest(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn test_quote_name() {
+        assert_eq!(PostgresDialect.quote_name("movies.movie.title"), "\"movies\".\"movie\".\"title\"");
+        assert_eq!(MySQLDialect::default().quote_name("movies.movie.title"), "`movies`.`movie`.`title`");
+
+        assert_eq!(PostgresDialect.quote_identifier("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(MySQLDialect::default().quote_identifier("a`b"), "`a``b`");
+    }
+
+    #[test]
+    fn test_string_literal() {
+        assert_eq!(PostgresDialect.string_literal("it's \\"), "'it''s \\'");
+        assert_eq!(MySQLDialect::default().string_literal("it's \\"), "'it''s \\\\'");
+    }
+
+    #[test]
+    fn test_mysql_collations() -> Result<(), Error> {
+        let mysql_8 = MySQLDialect::for_server_version("8.0.36");
+        let mysql_5 = MySQLDialect::for_server_version("5.7.44-log");
+        let mariadb = MySQLDialect::for_server_version("10.6.12-MariaDB");
+
+        assert!(mysql_8.unicode_9_collations);
+        assert!(!mysql_5.unicode_9_collations);
+        assert!(!mariadb.unicode_9_collations);
+
+        let compare = |dialect: &MySQLDialect, unaccent: bool| {
+            dialect.compare_ignoring_case("`movies`.`movie`.`title`", &Operator::EqualToIgnoreCase, "?", unaccent)
+        };
+
+        assert_eq!(
+            compare(&mysql_8, true)?,
+            "CONVERT(`movies`.`movie`.`title` USING utf8mb4) COLLATE utf8mb4_0900_ai_ci = ?"
+        );
+        assert_eq!(
+            compare(&mysql_8, false)?,
+            "CONVERT(`movies`.`movie`.`title` USING utf8mb4) COLLATE utf8mb4_0900_as_ci = ?"
+        );
+        assert_eq!(
+            compare(&mysql_5, true)?,
+            "CONVERT(`movies`.`movie`.`title` USING utf8mb4) COLLATE utf8mb4_unicode_ci = ?"
+        );
+        assert_eq!(
+            compare(&mariadb, false)?,
+            "LOWER(CONVERT(`movies`.`movie`.`title` USING utf8mb4)) COLLATE utf8mb4_bin = LOWER(?)"
+        );
+
+        Ok(())
+    }
+}
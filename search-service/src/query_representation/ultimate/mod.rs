@@ -1,50 +1,341 @@
 /*
     Module responsible for creating the final (SQL) representation
     of a query. It receives the command created in the intermediary representation
-    and the projection coming from the initial representation.
+    and the projection coming from the initial representation. Values of the
+    filters never go into the SQL text, they are bound as parameters.
 */
 
-use anyhow::Error;
+use std::collections::HashMap;
 
+use anyhow::{anyhow, Error};
+use serde::{Deserialize, Serialize};
+use strum_macros::Display;
+
+pub mod dialect;
 pub mod test_utils;
 
+const PATTERN_ESCAPE: char = '!';
+
 use crate::{
     query_representation::intermediary::{
-        get_command_attributes, single_command::DataType,
-        single_command::Operator, Command,
+        composite_command::{CompositeCommand, LogicalOperator}, get_command_attributes, single_command::DataType,
+        single_command::Operator, single_command::Value, Command,
     },
     relational::table_search::TableSearch,
 };
 
+use self::dialect::SqlDialect;
+
+// SQL text of a query and the values of its placeholders, in order.
+#[derive(PartialEq, Debug, Clone)]
+pub struct Query {
+    pub sql: String,
+    pub parameters: Vec<QueryParameter>,
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub enum QueryParameter {
+    Integer(i64),
+    Float(f64),
+    String(String),
+    // bound as text in the YYYY-MM-DD format
+    Date(String),
+}
+
+impl Query {
+    pub fn new(sql: String, parameters: Vec<QueryParameter>) -> Self {
+        Self { sql, parameters }
+    }
+}
+
+// Shape of the query besides its filters, chosen by each search.
+#[derive(Default, PartialEq, Debug, Clone)]
+pub struct QueryOptions {
+    // tables (schema.table) whose rows may be missing, they are joined with LEFT JOIN
+    pub optional_tables: Vec<String>,
+    // sort of the results, as asked by SearchOptions::order_by
+    pub order_by: Vec<OrderBy>,
+    // slice of the results returned, all of them when missing
+    pub page: Option<Page>,
+    // counts the rows matching the filters instead of returning them
+    pub count: bool,
+    // attributes also selected as cursor_0, cursor_1, ... so the next page starts after the last row
+    pub cursor_attributes: Vec<String>,
+    // aggregates selected after the projection, one row is returned for each group
+    pub aggregates: Vec<Aggregate>,
+    // attributes the rows are grouped by before being aggregated
+    pub group_by: Vec<String>,
+    // filters of the groups, naming aggregates by their alias
+    pub having: Option<Command>,
+    // repeated rows are returned only once
+    pub distinct: bool,
+    // tables only used by the filters are checked with EXISTS when they would repeat rows
+    pub semi_joins: bool,
+    // case insensitive operators also ignore accents
+    pub ignore_accents: bool,
+}
+
+impl QueryOptions {
+    pub fn is_aggregated(&self) -> bool {
+        !self.aggregates.is_empty() || !self.group_by.is_empty()
+    }
+
+    // rows are counted on the query itself rather than straight from the tables
+    fn counts_results(&self) -> bool {
+        self.is_aggregated() || self.distinct
+    }
+
+    // the aggregate named by an attribute of the order, if any
+    pub fn find_aggregate(&self, name: &str) -> Option<&Aggregate> {
+        self.aggregates.iter().find(|a| a.name() == name)
+    }
+}
+
+// How rows repeated by joining tables with many rows for each one are avoided.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Deduplication {
+    // tables only used by the filters are checked with EXISTS instead of being joined
+    #[default]
+    SemiJoin,
+    // repeated rows are returned once
+    Distinct,
+    // a row is returned for each joined row
+    None,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct Aggregate {
+    pub function: AggregateFunction,
+    // attribute aggregated, count counts the rows when it is missing
+    #[serde(default)]
+    pub attribute: Option<String>,
+    // column of the aggregate in the results, named after the function and attribute when missing
+    #[serde(default)]
+    pub alias: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Display)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum AggregateFunction {
+    Count,
+    CountDistinct,
+    Sum,
+    Avg,
+    Min,
+    Max,
+}
+
+impl Aggregate {
+    // e.g. avg_budget for the average of movies.movie.budget
+    pub fn name(&self) -> String {
+        match (&self.alias, &self.attribute) {
+            (Some(alias), _) => alias.to_owned(),
+            (None, Some(attribute)) => {
+                let attribute_name = attribute.rsplit('.').next().unwrap_or(attribute);
+                format!("{}_{}", self.function, attribute_name)
+            }
+            (None, None) => self.function.to_string(),
+        }
+    }
+
+    fn expression(&self, dialect: &dyn SqlDialect) -> Result<String, Error> {
+        let attribute = match (&self.attribute, &self.function) {
+            (None, AggregateFunction::Count) => return Ok("COUNT(*)".to_string()),
+            (Some(attribute), _) => dialect.quote_name(attribute),
+            (None, function) => return Err(anyhow!("{} needs an attribute", function)),
+        };
+
+        let expression = match self.function {
+            AggregateFunction::Count => format!("COUNT({})", attribute),
+            AggregateFunction::CountDistinct => format!("COUNT(DISTINCT {})", attribute),
+            AggregateFunction::Sum => format!("SUM({})", attribute),
+            AggregateFunction::Avg => format!("AVG({})", attribute),
+            AggregateFunction::Min => format!("MIN({})", attribute),
+            AggregateFunction::Max => format!("MAX({})", attribute),
+        };
+
+        Ok(expression)
+    }
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct Page {
+    // most rows returned
+    pub limit: u64,
+    // rows skipped before the first one returned
+    pub offset: u64,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct OrderBy {
+    pub attribute: String,
+    #[serde(default)]
+    pub direction: Direction,
+    // where missing values go, the database decides when left out
+    #[serde(default)]
+    pub nulls: Option<NullsOrder>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Direction {
+    #[default]
+    Asc,
+    Desc,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[serde(rename_all = "lowercase")]
+pub enum NullsOrder {
+    First,
+    Last,
+}
+
+impl TryFrom<&Value> for QueryParameter {
+    type Error = Error;
+
+    fn try_from(value: &Value) -> Result<Self, Self::Error> {
+        let literal = value.value.to_owned();
+
+        let parameter = match value.data_type {
+            DataType::Integer => QueryParameter::Integer(
+                literal
+                    .parse()
+                    .map_err(|_| anyhow!("Value {} is not an integer", literal))?,
+            ),
+            DataType::Float => QueryParameter::Float(
+                literal
+                    .parse()
+                    .map_err(|_| anyhow!("Value {} is not a number", literal))?,
+            ),
+            DataType::String => QueryParameter::String(literal),
+            DataType::Date => QueryParameter::Date(literal),
+            _ => return Err(anyhow!("{} values can't be bound", value.data_type)),
+        };
+
+        Ok(parameter)
+    }
+}
+
 pub fn command_to_query(
     projection: Vec<String>,
     command: &Command,
     table_search: &TableSearch,
-) -> Result<String, Error> {
+    dialect: &dyn SqlDialect,
+    options: &QueryOptions,
+) -> Result<Query, Error> {
+
+    let join_plan = plan_query_joins(&projection, command, table_search, options)?;
+
+    // projected values are returned as text
+    let projection_columns = projection
+        .iter()
+        .map(|attribute| dialect.cast_to_text(&dialect.quote_name(attribute)));
+    let cursor_columns = options
+        .cursor_attributes
+        .iter()
+        .enumerate()
+        .map(|(idx, attribute)| {
+            format!("{} AS cursor_{}", dialect.cast_to_text(&dialect.quote_name(attribute)), idx)
+        });
+    let aggregate_columns = options
+        .aggregates
+        .iter()
+        .map(|aggregate| create_aggregate_column(aggregate, dialect))
+        .collect::<Result<Vec<String>, Error>>()?;
+    let columns = projection_columns
+        .chain(aggregate_columns)
+        .chain(cursor_columns)
+        .collect();
+
+    // groups and distinct rows are counted on the query, rows straight from the tables
+    let select_query = if options.count && !options.counts_results() {
+        create_count_query(dialect)
+    } else {
+        create_select_query(columns)
+    };
 
-    let attributes_needed = get_attributes_needed(projection.clone(),command)?;
+    // distinct rows are grouped by the projection rather than selected with DISTINCT,
+    // so they can still be sorted by attributes that are projected as text
+    let group_by = match options.distinct && !options.is_aggregated() {
+        true => projection,
+        false => options.group_by.clone(),
+    };
 
-    let (tables_needed, atributes_pairs_for_join) =
-        table_search.get_join_requirements(&attributes_needed);
+    let where_query = create_where_query(command, dialect, options.ignore_accents)?;
+    let mut parameters = where_query.parameters;
 
-    let select_query = create_select_query(projection);
-    let from_query = create_from_query(tables_needed);
+    let from_query = create_from_query(
+        &join_plan.root_table,
+        &join_plan.tables,
+        &join_plan.pairs,
+        &options.optional_tables,
+        dialect,
+    )?;
 
-    let where_query = create_where_query(command, &atributes_pairs_for_join)?;
+    let where_query = match &join_plan.semi_joins {
+        Some(joins) => create_semi_join_query(joins, &where_query.sql, dialect),
+        None => where_query.sql,
+    };
 
-    let mut final_query = [select_query, from_query, where_query].join("\n");
+    let mut query_sections = vec![select_query, from_query, where_query];
 
-    final_query.push_str(";");
-    Ok(final_query)
-}
+    if !group_by.is_empty() {
+        let group_by: Vec<String> = group_by.iter().map(|g| dialect.quote_name(g)).collect();
+        query_sections.push(format!("GROUP BY {}", group_by.join(", ")));
+    }
+
+    // aggregates are named by their alias in the filters of the groups, the query
+    // repeats their expression
+    if let Some(having) = &options.having {
+        query_sections.push(create_where_for_command(
+            "HAVING ".to_owned(),
+            having,
+            dialect,
+            options.ignore_accents,
+            &options.aggregates,
+            &mut parameters,
+        )?);
+    }
+
+    if options.count && options.counts_results() {
+        query_sections = vec![
+            create_count_query(dialect),
+            format!("FROM ({}) AS results", query_sections.join("\n")),
+        ];
+    }
+
+    // the order and the page don't change how many rows match
+    if !options.count {
+        if !options.order_by.is_empty() {
+            query_sections.push(create_order_by_query(&options.order_by, &options.aggregates, dialect)?);
+        }
 
-fn get_attributes_needed(projection: Vec<String>, command: &Command) -> Result<Vec<String>,Error> {
-    let mut attributes_needed = vec![];
-    for p in projection.iter() {
-        let attribute = p.replace("::TEXT","");
-        attributes_needed.push(attribute);
+        if let Some(page) = &options.page {
+            query_sections.push(dialect.limit(page));
+        }
     }
+
+    let mut final_query = query_sections.join("\n");
+
+    final_query.push(';');
+    Ok(Query::new(final_query, parameters))
+}
+
+pub fn get_attributes_needed(projection: Vec<String>, command: &Command, options: &QueryOptions) -> Result<Vec<String>,Error> {
+    let mut attributes_needed = projection;
     attributes_needed.extend(get_command_attributes(command));
+    attributes_needed.extend(
+        options
+            .order_by
+            .iter()
+            .filter(|o| options.find_aggregate(&o.attribute).is_none())
+            .map(|o| o.attribute.to_owned()),
+    );
+    attributes_needed.extend(options.group_by.iter().cloned());
+    attributes_needed.extend(options.aggregates.iter().filter_map(|a| a.attribute.to_owned()));
     attributes_needed.sort();
     attributes_needed.dedup();
     Ok(attributes_needed)
@@ -64,130 +355,500 @@ fn create_select_query(projection: Vec<String>) -> String {
     select_query
 }
 
-fn create_from_query(tables: Vec<String>) -> String {
-    let mut from_query = "FROM ".to_owned();
-    let len = tables.len();
+// Joins the tables needed to the root table one at a time, following the foreign keys
+// found by the table search (pairs of attributes as "a.b.c:x.y.z"). Tables reached
+// through an optional table are optional too, or its missing rows would be dropped.
+fn create_from_query(
+    root_table: &str,
+    tables: &[String],
+    join_atribute_pairs: &[String],
+    optional_tables: &[String],
+    dialect: &dyn SqlDialect,
+) -> Result<String, Error> {
+    let mut from_query = format!("FROM {}", dialect.quote_name(root_table));
+
+    // tables already joined and whether they are optional
+    let mut joined_tables: HashMap<String, bool> = HashMap::from([(root_table.to_owned(), false)]);
+
+    for join in plan_joins(&mut joined_tables, join_atribute_pairs, optional_tables)? {
+        let join_type = match join.optional {
+            true => "LEFT JOIN",
+            false => "JOIN",
+        };
+        from_query.push_str(&format!(
+            "\n{} {} ON {}",
+            join_type,
+            dialect.quote_name(&join.table),
+            create_join_conditions(&join.conditions, dialect)
+        ));
+    }
 
-    for (idx, table) in tables.iter().enumerate() {
-        from_query.push_str(table);
-        if idx != len - 1 {
-            from_query.push_str(", ");
-        }
+    if let Some(table) = tables.iter().find(|t| !joined_tables.contains_key(*t)) {
+        return Err(anyhow!("Table {} can't be joined to {}", table, root_table));
     }
 
-    from_query
+    Ok(from_query)
 }
 
-fn create_where_query(
+// A table joined to the ones before it, reached by its first condition.
+struct Join {
+    table: String,
+    optional: bool,
+    // the joined attribute and the attribute of the new table it is reached by
+    reached_by: (String, String),
+    // pairs of attributes that are equal
+    conditions: Vec<(String, String)>,
+}
+
+// Joins the tables of the pairs to those already joined, each one as soon as it is reached.
+fn plan_joins(
+    joined_tables: &mut HashMap<String, bool>,
+    join_atribute_pairs: &[String],
+    optional_tables: &[String],
+) -> Result<Vec<Join>, Error> {
+    let mut joins = Vec::new();
+
+    let mut pairs = join_atribute_pairs
+        .iter()
+        .map(|pair| {
+            pair.split_once(':')
+                .map(|(a, b)| (a.to_owned(), b.to_owned()))
+                .ok_or_else(|| anyhow!("Invalid join between attributes {}", pair))
+        })
+        .collect::<Result<Vec<(String, String)>, Error>>()?;
+
+    while let Some(idx) = pairs.iter().position(|(a, b)| {
+        joined_tables.contains_key(table_of(a)) != joined_tables.contains_key(table_of(b))
+    }) {
+        let (a, b) = pairs.remove(idx);
+        let (joined_attribute, new_attribute) = match joined_tables.contains_key(table_of(&a)) {
+            true => (a, b),
+            false => (b, a),
+        };
+
+        let table = table_of(&new_attribute).to_owned();
+        let optional = joined_tables[table_of(&joined_attribute)] || optional_tables.contains(&table);
+        joined_tables.insert(table.clone(), optional);
+
+        let mut conditions = vec![(joined_attribute.clone(), new_attribute.clone())];
+
+        // other foreign keys between the new table and the ones already joined
+        pairs.retain(|(a, b)| {
+            let closes_cycle = (table_of(a) == table || table_of(b) == table)
+                && joined_tables.contains_key(table_of(a))
+                && joined_tables.contains_key(table_of(b));
+            if closes_cycle {
+                conditions.push((a.to_owned(), b.to_owned()));
+            }
+            !closes_cycle
+        });
+
+        joins.push(Join {
+            table,
+            optional,
+            reached_by: (joined_attribute, new_attribute),
+            conditions,
+        });
+    }
+
+    Ok(joins)
+}
+
+// Tables of a query and how they are joined to its root table. Tables only used by the
+// filters may be left to an EXISTS subquery.
+struct JoinPlan {
+    root_table: String,
+    tables: Vec<String>,
+    pairs: Vec<String>,
+    semi_joins: Option<Vec<Join>>,
+}
+
+fn plan_query_joins(
+    projection: &[String],
     command: &Command,
-    join_atribute_pairs: &Vec<String>,
-) -> Result<String, Error> {
+    table_search: &TableSearch,
+    options: &QueryOptions,
+) -> Result<JoinPlan, Error> {
+    let attributes_needed = get_attributes_needed(projection.to_vec(), command, options)?;
 
-    let mut where_query = "WHERE ".to_owned();
+    let (tables_needed, atributes_pairs_for_join) =
+        table_search.get_join_requirements(&attributes_needed);
 
-    where_query = create_where_for_join(where_query.to_owned(),join_atribute_pairs)?;
-    
-    if join_atribute_pairs.len() > 0{
-        where_query.push_str(" AND ");
+    // the other tables are joined to a projected table whose rows are all kept, the
+    // first one by name, so the rows returned don't depend on the order of the projection
+    let mut projected_tables: Vec<&str> = projection.iter().map(|p| table_of(p)).collect();
+    projected_tables.sort();
+    let root_table = projected_tables
+        .iter()
+        .find(|table| !options.optional_tables.iter().any(|t| t == *table))
+        .or_else(|| projected_tables.first())
+        .map(|table| table.to_string())
+        .or_else(|| tables_needed.first().cloned())
+        .ok_or_else(|| anyhow!("The query needs at least one table"))?;
+
+    let join_plan = JoinPlan {
+        root_table,
+        tables: tables_needed,
+        pairs: atributes_pairs_for_join,
+        semi_joins: None,
+    };
+
+    // aggregates are computed over every joined row, the filter tables included
+    if !options.semi_joins || options.is_aggregated() {
+        return Ok(join_plan);
     }
 
-    where_query = create_where_for_command(where_query.to_owned(),command)?;
+    let no_filters = Command::CompositeCommand(CompositeCommand::new(LogicalOperator::And, vec![]));
+    let projected_attributes = get_attributes_needed(projection.to_vec(), &no_filters, options)?;
 
+    Ok(plan_semi_join(&projected_attributes, table_search, options, &join_plan)?.unwrap_or(join_plan))
+}
 
-    Ok(where_query)
+// Tables only used by the filters are moved to an EXISTS subquery when one of them holds
+// many rows for each row of the tables before it, so the projected rows aren't repeated
+// for each of them. The conditions joining them to the projected tables correlate the subquery.
+fn plan_semi_join(
+    projected_attributes: &[String],
+    table_search: &TableSearch,
+    options: &QueryOptions,
+    join_plan: &JoinPlan,
+) -> Result<Option<JoinPlan>, Error> {
+    if projected_attributes.is_empty() {
+        return Ok(None);
+    }
+
+    let (projected_tables, projected_pairs) =
+        table_search.get_join_requirements(&projected_attributes.to_vec());
+
+    let filter_tables: Vec<&String> = join_plan
+        .tables
+        .iter()
+        .filter(|t| !projected_tables.contains(t))
+        .collect();
+
+    if filter_tables.is_empty() {
+        return Ok(None);
+    }
+
+    let root_table = match projected_tables.contains(&join_plan.root_table) {
+        true => join_plan.root_table.to_owned(),
+        false => projected_tables[0].to_owned(),
+    };
+
+    let mut joined_tables: HashMap<String, bool> = HashMap::from([(root_table.clone(), false)]);
+    plan_joins(&mut joined_tables, &projected_pairs, &options.optional_tables)?;
+    let joins = plan_joins(&mut joined_tables, &join_plan.pairs, &options.optional_tables)?;
+
+    // rows of optional tables may be missing, which an EXISTS wouldn't tell
+    if joins.iter().any(|join| join.optional)
+        || filter_tables.iter().any(|t| !joined_tables.contains_key(*t))
+    {
+        return Ok(None);
+    }
+
+    let fans_out = joins.iter().any(|join| {
+        let (joined_attribute, new_attribute) = &join.reached_by;
+        !table_search.references(joined_attribute, new_attribute)
+    });
+
+    if !fans_out {
+        return Ok(None);
+    }
+
+    Ok(Some(JoinPlan {
+        root_table,
+        tables: projected_tables,
+        pairs: projected_pairs,
+        semi_joins: Some(joins),
+    }))
 }
 
-fn create_where_for_join(mut where_query: String,join_atribute_pairs: &Vec<String>) -> Result<String, Error>{
+// Tables whose rows make up the results of the query, each result row stands for one
+// combination of their rows.
+pub fn get_result_tables(
+    projection: &[String],
+    command: &Command,
+    table_search: &TableSearch,
+    options: &QueryOptions,
+) -> Result<Vec<String>, Error> {
+    Ok(plan_query_joins(projection, command, table_search, options)?.tables)
+}
 
-    if join_atribute_pairs.len() > 0{
-        where_query.push_str("(");
+// The filters move to the subquery, next to the conditions correlating it.
+fn create_semi_join_query(joins: &[Join], where_query: &str, dialect: &dyn SqlDialect) -> String {
+    let condition = where_query.trim_start_matches("WHERE ");
+
+    let mut subquery = String::from("SELECT 1");
+    let mut correlations: Vec<String> = Vec::new();
+
+    for (idx, join) in joins.iter().enumerate() {
+        let table = dialect.quote_name(&join.table);
+        if idx == 0 {
+            subquery.push_str(&format!("\nFROM {}", table));
+            correlations.push(create_join_conditions(&join.conditions, dialect));
+        } else {
+            subquery.push_str(&format!(
+                "\nJOIN {} ON {}",
+                table,
+                create_join_conditions(&join.conditions, dialect)
+            ));
+        }
     }
 
-    for pair in join_atribute_pairs {
-        let atributes: Vec<&str> = pair.split(":").collect();
+    correlations.push(condition.to_owned());
+    subquery.push_str(&format!("\nWHERE {}", correlations.join(" AND ")));
+
+    format!("WHERE EXISTS ({})", subquery)
+}
+
+fn create_join_conditions(conditions: &[(String, String)], dialect: &dyn SqlDialect) -> String {
+    conditions
+        .iter()
+        .map(|(a, b)| format!("{} = {}", dialect.quote_name(a), dialect.quote_name(b)))
+        .collect::<Vec<String>>()
+        .join(" AND ")
+}
+
+// schema.table of a schema.table.attribute name
+fn table_of(attribute: &str) -> &str {
+    attribute
+        .rsplit_once('.')
+        .map(|(table, _)| table)
+        .unwrap_or(attribute)
+}
+
+// Attributes are sorted by their own type, not by the text they are projected as, and
+// aggregates by their value rather than by the text of their column.
+fn create_order_by_query(
+    order_by: &[OrderBy],
+    aggregates: &[Aggregate],
+    dialect: &dyn SqlDialect,
+) -> Result<String, Error> {
+    let mut sort_keys: Vec<String> = Vec::new();
+
+    for o in order_by {
+        let expression = translate_attribute(&o.attribute, aggregates, dialect)?;
+
+        match &o.nulls {
+            None => sort_keys.push(format!("{} {}", expression, translate_direction(&o.direction))),
+            Some(nulls) => sort_keys.push(dialect.sort_key(&expression, &o.direction, nulls)),
+        }
+    }
 
-        let section = format!("{} = {} AND ", atributes[0], atributes[1]);
+    Ok(format!("ORDER BY {}", sort_keys.join(", ")))
+}
 
-        where_query.push_str(&section);
+// An attribute as written in the query, aggregates are named by their alias and
+// written as their expression.
+fn translate_attribute(
+    name: &str,
+    aggregates: &[Aggregate],
+    dialect: &dyn SqlDialect,
+) -> Result<String, Error> {
+    match aggregates.iter().find(|a| a.name() == name) {
+        Some(aggregate) => aggregate.expression(dialect),
+        None => Ok(dialect.quote_name(name)),
     }
+}
 
-    if join_atribute_pairs.len() > 0{
-        where_query = where_query[0..where_query.len()-5].to_string();
-        where_query.push_str(")");
-    } 
+fn create_aggregate_column(aggregate: &Aggregate, dialect: &dyn SqlDialect) -> Result<String, Error> {
+    Ok(format!(
+        "{} AS {}",
+        dialect.cast_to_text(&aggregate.expression(dialect)?),
+        dialect.quote_identifier(&aggregate.name())
+    ))
+}
 
-    Ok(where_query)
+fn create_count_query(dialect: &dyn SqlDialect) -> String {
+    format!("SELECT {} AS total_count", dialect.cast_to_text("COUNT(*)"))
 }
 
-fn create_where_for_command(mut where_query: String ,command: &Command) -> Result<String, Error> {
+fn create_where_query(
+    command: &Command,
+    dialect: &dyn SqlDialect,
+    unaccent: bool,
+) -> Result<Query, Error> {
+
+    let mut where_query = "WHERE ".to_owned();
+    let mut parameters = vec![];
+
+    where_query = create_where_for_command(where_query.to_owned(),command,dialect,unaccent,&[],&mut parameters)?;
+
+
+    Ok(Query::new(where_query, parameters))
+}
+
+fn create_where_for_command(
+    mut where_query: String,
+    command: &Command,
+    dialect: &dyn SqlDialect,
+    unaccent: bool,
+    aggregates: &[Aggregate],
+    parameters: &mut Vec<QueryParameter>,
+) -> Result<String, Error> {
     
-    where_query.push_str("(");
+    where_query.push('(');
     
     match command {
         Command::CompositeCommand(composite_command) => {
             let nested_commands = &composite_command.commands;
 
-
-            let logical_operator = format!(" {} ", composite_command.logical_operator.to_string());
-            where_query = create_where_for_command(where_query.to_owned(),&nested_commands[0])?;
-            where_query.push_str(&logical_operator);
-            where_query = create_where_for_command(where_query.to_owned(),&nested_commands[1])?;
-
+            if let LogicalOperator::Not = composite_command.logical_operator {
+                if nested_commands.len() != 1 {
+                    return Err(anyhow!("NOT must be applied to exactly one command"));
+                }
+
+                where_query.push_str("NOT ");
+                where_query = create_where_for_command(where_query.to_owned(),&nested_commands[0],dialect,unaccent,aggregates,parameters)?;
+            } else {
+                // an AND without commands is always true, an OR without commands never is
+                if nested_commands.is_empty() {
+                    match composite_command.logical_operator {
+                        LogicalOperator::And => where_query.push_str("1 = 1"),
+                        _ => where_query.push_str("1 = 0"),
+                    }
+                }
+
+                let logical_operator = format!(" {} ", composite_command.logical_operator);
+                for (idx, nested_command) in nested_commands.iter().enumerate() {
+                    if idx > 0 {
+                        where_query.push_str(&logical_operator);
+                    }
+                    where_query = create_where_for_command(where_query.to_owned(),nested_command,dialect,unaccent,aggregates,parameters)?;
+                }
+            }
         }
 
         Command::SingleCommand(single_command) => {
-            where_query.push_str(&single_command.attribute);
-            where_query.push_str(&translate_operator(&single_command.operator)?);
-
-            if let DataType::String = &single_command.value.data_type {
-                where_query.push_str("'");
-                where_query.push_str(&single_command.value.value);
-                where_query.push_str("'");
-            }
-            else{
-                where_query.push_str(&single_command.value.value);
+            let operator = &single_command.operator;
+            let attribute = translate_attribute(&single_command.attribute, aggregates, dialect)?;
+
+            let value = if operator.is_pattern() {
+                let pattern = translate_pattern(&operator.case_sensitive(), &single_command.value.value);
+                push_parameter(QueryParameter::String(pattern), dialect, parameters)
+            } else {
+                translate_value(&single_command.value, dialect, aggregates, parameters)?
+            };
+
+            if operator.is_case_insensitive() {
+                where_query.push_str(&dialect.compare_ignoring_case(&attribute, operator, &value, unaccent)?);
+            } else {
+                where_query.push_str(&attribute);
+                where_query.push_str(&translate_operator(operator)?);
+                where_query.push_str(&value);
             }
 
+            if operator.is_pattern() {
+                let escape = dialect.string_literal(&PATTERN_ESCAPE.to_string());
+                where_query.push_str(&format!(" ESCAPE {}", escape));
+            }
         }
     }
 
-    where_query.push_str(")");
+    where_query.push(')');
 
     Ok(where_query)
 }
 
 fn translate_operator(operator: &Operator) -> Result<String, Error> {
-    let operator_translated;
+    let operator_translated = match operator {
+        Operator::EqualTo => " = ",
+        Operator::GreaterThan => " > ",
+        Operator::LessThan => " < ",
+        Operator::GreaterThanOrEqualTo => " >= ",
+        Operator::LessThanOrEqualTo => " <= ",
+        Operator::NotEqualTo => " <> ",
+        Operator::Contains
+        | Operator::StartsWith
+        | Operator::EndsWith
+        | Operator::Like => " LIKE ",
+        Operator::EqualToIgnoreCase
+        | Operator::NotEqualToIgnoreCase
+        | Operator::ContainsIgnoreCase
+        | Operator::StartsWithIgnoreCase
+        | Operator::EndsWithIgnoreCase
+        | Operator::LikeIgnoreCase => return translate_operator(&operator.case_sensitive()),
+        Operator::In => " IN ",
+        Operator::NotIn => " NOT IN ",
+        Operator::Between => " BETWEEN ",
+        Operator::IsNull => " IS NULL",
+        Operator::IsNotNull => " IS NOT NULL",
+    };
 
-    match operator {
-        Operator::EqualTo => {
-            operator_translated = " = ".to_owned();
-        }
+    Ok(operator_translated.to_owned())
+}
 
-        Operator::GreaterThan => {
-            operator_translated = " > ".to_owned();
-        }
+fn translate_direction(direction: &Direction) -> &'static str {
+    match direction {
+        Direction::Asc => "ASC",
+        Direction::Desc => "DESC",
+    }
+}
 
-        Operator::LessThan => {
-            operator_translated = " < ".to_owned();
-        }
+// Attributes are written in the query, every other value becomes a parameter.
+fn translate_value(
+    value: &Value,
+    dialect: &dyn SqlDialect,
+    aggregates: &[Aggregate],
+    parameters: &mut Vec<QueryParameter>,
+) -> Result<String, Error> {
+    let mut translate_values = |values: &Vec<Value>| -> Result<Vec<String>, Error> {
+        values
+            .iter()
+            .map(|v| translate_value(v, dialect, aggregates, parameters))
+            .collect()
+    };
 
-        Operator::GreaterThanOrEqualTo => {
-            operator_translated = " >= ".to_owned();
-        }
+    let value_translated = match value.data_type {
+        DataType::Attribute => translate_attribute(&value.value, aggregates, dialect)?,
+        DataType::List => format!("({})", translate_values(&value.list)?.join(", ")),
+        DataType::Null => String::new(),
+        DataType::Range => translate_values(&value.list)?.join(" AND "),
+        _ => push_parameter(QueryParameter::try_from(value)?, dialect, parameters),
+    };
 
-        Operator::LessThanOrEqualTo => {
-            operator_translated = " <= ".to_owned();
-        }
+    Ok(value_translated)
+}
+
+// Adds a parameter to the query, returning its placeholder.
+fn push_parameter(
+    parameter: QueryParameter,
+    dialect: &dyn SqlDialect,
+    parameters: &mut Vec<QueryParameter>,
+) -> String {
+    let placeholder = dialect.placeholder(&parameter, parameters.len() + 1);
+
+    parameters.push(parameter);
+
+    placeholder
+}
 
-        Operator::NotEqualTo => {
-            operator_translated = " <> ".to_owned();
+// Builds the LIKE pattern of a pattern operator. Wildcards typed by the user are
+// escaped for every operator but Like, whose value already is a pattern. The escape
+// character is not a backslash so the pattern reads the same in Postgres and MySQL.
+fn translate_pattern(operator: &Operator, value: &str) -> String {
+    if let Operator::Like = operator {
+        return value.to_owned();
+    }
+
+    let mut escaped_value = String::new();
+    for c in value.chars() {
+        if c == PATTERN_ESCAPE || c == '%' || c == '_' {
+            escaped_value.push(PATTERN_ESCAPE);
         }
+        escaped_value.push(c);
+    }
+
+    match operator {
+        Operator::Contains => format!("%{}%", escaped_value),
+        Operator::StartsWith => format!("{}%", escaped_value),
+        Operator::EndsWith => format!("%{}", escaped_value),
+        _ => escaped_value,
     }
-    Ok(operator_translated)
 }
 
 #[cfg(test)]
+#[allow(clippy::vec_init_then_push)]
 mod tests {
 
     use crate::query_representation::intermediary::Command;
@@ -209,7 +870,11 @@ mod tests {
     use anyhow::Error;
 
     use super::create_from_query;
+    use super::dialect::{MySQLDialect, PostgresDialect};
+    use super::create_order_by_query;
+    use super::{Aggregate, AggregateFunction, Direction, NullsOrder, OrderBy, Page};
     use super::create_where_query;
+    use super::{Query, QueryOptions, QueryParameter};
 
     #[test]
     fn test_create_select_query() {
@@ -220,18 +885,78 @@ mod tests {
     }
 
     #[test]
-    fn test_create_from_query() {
-        let tables = vec!["table1".into(), "table2".into(), "table3".into()];
-        let from_query = create_from_query(tables);
+    fn test_create_from_query() -> Result<(), Error> {
+        let tables: Vec<String> = vec![
+            "movies.movie".into(),
+            "movies.movie_cast".into(),
+            "movies.person".into(),
+            "movies.production_company".into(),
+        ];
+        let atributes_pairs_for_join: Vec<String> = vec![
+            "movies.movie.movie_id:movies.movie_cast.movie_id".into(),
+            "movies.movie_cast.person_id:movies.person.person_id".into(),
+            "movies.movie.company_id:movies.production_company.company_id".into(),
+            "movies.movie.director_id:movies.person.person_id".into(),
+        ];
+
+        assert_eq!(
+            create_from_query("movies.movie", &tables[..1], &[], &[], &PostgresDialect)?,
+            "FROM \"movies\".\"movie\""
+        );
+
+        assert_eq!(
+            create_from_query("movies.movie", &tables, &atributes_pairs_for_join, &[], &PostgresDialect)?,
+            "FROM \"movies\".\"movie\"\n\
+            JOIN \"movies\".\"movie_cast\" ON \"movies\".\"movie\".\"movie_id\" = \"movies\".\"movie_cast\".\"movie_id\"\n\
+            JOIN \"movies\".\"person\" ON \"movies\".\"movie_cast\".\"person_id\" = \"movies\".\"person\".\"person_id\" AND \"movies\".\"movie\".\"director_id\" = \"movies\".\"person\".\"person_id\"\n\
+            JOIN \"movies\".\"production_company\" ON \"movies\".\"movie\".\"company_id\" = \"movies\".\"production_company\".\"company_id\""
+        );
 
-        assert_eq!(from_query, "FROM table1, table2, table3");
+        let optional_tables: Vec<String> = vec!["movies.movie_cast".into()];
+
+        assert_eq!(
+            create_from_query("movies.person", &tables, &atributes_pairs_for_join[..3], &optional_tables, &PostgresDialect)?,
+            "FROM \"movies\".\"person\"\n\
+            LEFT JOIN \"movies\".\"movie_cast\" ON \"movies\".\"person\".\"person_id\" = \"movies\".\"movie_cast\".\"person_id\"\n\
+            LEFT JOIN \"movies\".\"movie\" ON \"movies\".\"movie_cast\".\"movie_id\" = \"movies\".\"movie\".\"movie_id\"\n\
+            LEFT JOIN \"movies\".\"production_company\" ON \"movies\".\"movie\".\"company_id\" = \"movies\".\"production_company\".\"company_id\""
+        );
+
+        assert!(create_from_query("movies.movie", &tables, &atributes_pairs_for_join[..1], &[], &PostgresDialect).is_err());
+
+        Ok(())
     }
 
 
     #[test]
-    fn test_create_where_query_1() -> Result<(), Error> {
+    fn test_create_order_by_query() -> Result<(), Error> {
+        let order_by = vec![
+            OrderBy {
+                attribute: "movies.movie.revenue".into(),
+                direction: Direction::Desc,
+                nulls: Some(NullsOrder::Last),
+            },
+            OrderBy {
+                attribute: "movies.movie.title".into(),
+                direction: Direction::Asc,
+                nulls: None,
+            },
+        ];
+
+        assert_eq!(
+            create_order_by_query(&order_by, &[], &PostgresDialect)?,
+            "ORDER BY \"movies\".\"movie\".\"revenue\" DESC NULLS LAST, \"movies\".\"movie\".\"title\" ASC"
+        );
+        assert_eq!(
+            create_order_by_query(&order_by, &[], &MySQLDialect::default())?,
+            "ORDER BY `movies`.`movie`.`revenue` IS NULL ASC, `movies`.`movie`.`revenue` DESC, `movies`.`movie`.`title` ASC"
+        );
 
-        let atributes_pairs_for_join = vec![];
+        Ok(())
+    }
+
+    #[test]
+    fn test_create_where_query_1() -> Result<(), Error> {
 
         let composite_command = CompositeCommand::new(LogicalOperator::Or, vec![
             Command::SingleCommand(SingleCommand::new(
@@ -248,13 +973,16 @@ mod tests {
 
         let command = Command::CompositeCommand(composite_command);
 
-        let query = create_where_query(&command, &atributes_pairs_for_join)?;
+        let query = create_where_query(&command, &PostgresDialect, false)?;
 
         assert_eq!(
             query,
-            format!(
-                "{}",
-                "WHERE ((movies.movie.title = 'Interstellar') OR (movies.movie.runtime > 300))",
+            Query::new(
+                "WHERE ((\"movies\".\"movie\".\"title\" = $1::TEXT) OR (\"movies\".\"movie\".\"runtime\" > $2::BIGINT))".into(),
+                vec![
+                    QueryParameter::String("Interstellar".into()),
+                    QueryParameter::Integer(300),
+                ],
             )
         );
 
@@ -265,11 +993,6 @@ mod tests {
   #[test]
     fn test_create_where_query_2() -> Result<(), Error> {
 
-        let atributes_pairs_for_join = vec![
-            "movies.movie.movie_id:movies.production_country.movie_id".into(),
-            "movies.production_country.country_id:movies.country.country_id".into(),
-        ];
-
         let composite_command_1 = CompositeCommand::new(LogicalOperator::Or, vec![
             Command::SingleCommand(SingleCommand::new(
                 "movies.country.country_name".to_string(),
@@ -294,29 +1017,330 @@ mod tests {
 
         let command = Command::CompositeCommand(composite_command_2);
 
-        let query = create_where_query(&command, &atributes_pairs_for_join)?;
+        let query = create_where_query(&command, &PostgresDialect, false)?;
 
         assert_eq!(
             query,
-            format!(
-                "{}",
-                "WHERE (movies.movie.movie_id = movies.production_country.movie_id AND \
-                movies.production_country.country_id = movies.country.country_id) AND \
-                (((movies.country.country_name = 'Brazil') OR \
-                (movies.country.country_name = 'United States')) AND \
-                (movies.movie.budget > 1000000))",
+            Query::new(
+                "WHERE (((\"movies\".\"country\".\"country_name\" = $1::TEXT) OR \
+                (\"movies\".\"country\".\"country_name\" = $2::TEXT)) AND \
+                (\"movies\".\"movie\".\"budget\" > $3::BIGINT))".into(),
+                vec![
+                    QueryParameter::String("Brazil".into()),
+                    QueryParameter::String("United States".into()),
+                    QueryParameter::Integer(1000000),
+                ],
+            )
+        );
+
+        Ok(())
+    }
+
+
+    #[test]
+    fn test_create_where_query_with_negation() -> Result<(), Error> {
+
+        let composite_command = CompositeCommand::new(LogicalOperator::Or, vec![
+            Command::SingleCommand(SingleCommand::new(
+                "movies.country.country_name".to_string(),
+                Operator::EqualTo,
+                Value::new("United States".into(), DataType::String),
+            )),
+            Command::SingleCommand(SingleCommand::new(
+                "movies.country.country_name".to_string(),
+                Operator::EqualTo,
+                Value::new("Brazil".into(), DataType::String),
+            )),
+        ]);
+
+        let command = Command::CompositeCommand(CompositeCommand::new(
+            LogicalOperator::Not,
+            vec![Command::CompositeCommand(composite_command)],
+        ));
+
+        let query = create_where_query(&command, &PostgresDialect, false)?;
+
+        assert_eq!(
+            query.sql,
+            "WHERE (NOT ((\"movies\".\"country\".\"country_name\" = $1::TEXT) OR (\"movies\".\"country\".\"country_name\" = $2::TEXT)))",
+        );
+
+        let invalid_command = Command::CompositeCommand(CompositeCommand::new(
+            LogicalOperator::Not,
+            vec![],
+        ));
+
+        assert!(create_where_query(&invalid_command, &PostgresDialect, false).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_create_where_query_with_pattern_operators() -> Result<(), Error> {
+
+        let expected_patterns = vec![
+            (Operator::Contains, "50% off_now!", "%50!% off!_now!!%"),
+            (Operator::StartsWith, "Star", "Star%"),
+            (Operator::EndsWith, "Wars", "%Wars"),
+            (Operator::Like, "St_r W%", "St_r W%"),
+        ];
+
+        for (operator, value, pattern) in expected_patterns {
+            let command = Command::SingleCommand(SingleCommand::new(
+                "movies.movie.title".to_string(),
+                operator,
+                Value::new(value.into(), DataType::String),
+            ));
+
+            let query = create_where_query(&command, &PostgresDialect, false)?;
+
+            assert_eq!(
+                query,
+                Query::new(
+                    "WHERE (\"movies\".\"movie\".\"title\" LIKE $1::TEXT ESCAPE '!')".into(),
+                    vec![QueryParameter::String(pattern.into())],
+                )
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_create_where_query_with_case_insensitive_operators() -> Result<(), Error> {
+
+        let equal_command = Command::SingleCommand(SingleCommand::new(
+            "movies.person.person_name".to_string(),
+            Operator::EqualToIgnoreCase,
+            Value::new("wagner moura".into(), DataType::String),
+        ));
+
+        let pattern_command = Command::SingleCommand(SingleCommand::new(
+            "movies.movie.title".to_string(),
+            Operator::StartsWithIgnoreCase,
+            Value::new("star".into(), DataType::String),
+        ));
+
+        assert_eq!(
+            create_where_query(&equal_command, &PostgresDialect, false)?.sql,
+            "WHERE (LOWER(\"movies\".\"person\".\"person_name\") = LOWER($1::TEXT))",
+        );
+        assert_eq!(
+            create_where_query(&equal_command, &PostgresDialect, true)?.sql,
+            "WHERE (LOWER(unaccent(\"movies\".\"person\".\"person_name\")) = LOWER(unaccent($1::TEXT)))",
+        );
+        assert_eq!(
+            create_where_query(&equal_command, &MySQLDialect::for_server_version("8.0.36"), true)?.sql,
+            "WHERE (CONVERT(`movies`.`person`.`person_name` USING utf8mb4) COLLATE utf8mb4_0900_ai_ci = ?)",
+        );
+
+        assert_eq!(
+            create_where_query(&pattern_command, &PostgresDialect, false)?,
+            Query::new(
+                "WHERE (\"movies\".\"movie\".\"title\" ILIKE $1::TEXT ESCAPE '!')".into(),
+                vec![QueryParameter::String("star%".into())],
+            )
+        );
+        assert_eq!(
+            create_where_query(&pattern_command, &PostgresDialect, true)?.sql,
+            "WHERE (unaccent(\"movies\".\"movie\".\"title\") ILIKE unaccent($1::TEXT) ESCAPE '!')",
+        );
+        assert_eq!(
+            create_where_query(&pattern_command, &MySQLDialect::for_server_version("8.0.36"), false)?.sql,
+            "WHERE (CONVERT(`movies`.`movie`.`title` USING utf8mb4) COLLATE utf8mb4_0900_as_ci LIKE ? ESCAPE '!')",
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_create_where_query_with_set_membership() -> Result<(), Error> {
+
+        let in_command = Command::SingleCommand(SingleCommand::new(
+            "movies.country.country_name".to_string(),
+            Operator::In,
+            Value::new_list(vec![
+                Value::new("Brazil".into(), DataType::String),
+                Value::new("United States".into(), DataType::String),
+            ]),
+        ));
+
+        let not_in_command = Command::SingleCommand(SingleCommand::new(
+            "movies.movie.runtime".to_string(),
+            Operator::NotIn,
+            Value::new_list(vec![
+                Value::new("90".into(), DataType::Integer),
+                Value::new("120".into(), DataType::Integer),
+            ]),
+        ));
+
+        assert_eq!(
+            create_where_query(&in_command, &PostgresDialect, false)?,
+            Query::new(
+                "WHERE (\"movies\".\"country\".\"country_name\" IN ($1::TEXT, $2::TEXT))".into(),
+                vec![
+                    QueryParameter::String("Brazil".into()),
+                    QueryParameter::String("United States".into()),
+                ],
+            )
+        );
+        assert_eq!(
+            create_where_query(&not_in_command, &MySQLDialect::for_server_version("8.0.36"), false)?,
+            Query::new(
+                "WHERE (`movies`.`movie`.`runtime` NOT IN (?, ?))".into(),
+                vec![QueryParameter::Integer(90), QueryParameter::Integer(120)],
+            )
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_create_where_query_with_range() -> Result<(), Error> {
+
+        let command = Command::SingleCommand(SingleCommand::new(
+            "movies.movie.release_date".to_string(),
+            Operator::Between,
+            Value::new_range(
+                Value::new("2000-01-01".into(), DataType::Date),
+                Value::new("2009-12-31".into(), DataType::Date),
+            ),
+        ));
+
+        assert_eq!(
+            create_where_query(&command, &PostgresDialect, false)?,
+            Query::new(
+                "WHERE (\"movies\".\"movie\".\"release_date\" BETWEEN $1::TEXT::DATE AND $2::TEXT::DATE)".into(),
+                vec![
+                    QueryParameter::Date("2000-01-01".into()),
+                    QueryParameter::Date("2009-12-31".into()),
+                ],
+            )
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_create_where_query_with_null_check() -> Result<(), Error> {
+
+        let command = Command::CompositeCommand(CompositeCommand::new(
+            LogicalOperator::Or,
+            vec![
+                Command::SingleCommand(SingleCommand::new(
+                    "movies.movie.homepage".to_string(),
+                    Operator::IsNull,
+                    Value::empty(),
+                )),
+                Command::SingleCommand(SingleCommand::new(
+                    "movies.movie.revenue".to_string(),
+                    Operator::IsNotNull,
+                    Value::empty(),
+                )),
+            ],
+        ));
+
+        assert_eq!(
+            create_where_query(&command, &PostgresDialect, false)?,
+            Query::new(
+                "WHERE ((\"movies\".\"movie\".\"homepage\" IS NULL) OR (\"movies\".\"movie\".\"revenue\" IS NOT NULL))".into(),
+                vec![],
             )
         );
 
         Ok(())
     }
 
+    #[test]
+    fn test_create_where_query_with_every_nested_command() -> Result<(), Error> {
+
+        let single_command = |attribute: &str, value: &str| {
+            Command::SingleCommand(SingleCommand::new(
+                attribute.to_string(),
+                Operator::EqualTo,
+                Value::new(value.into(), DataType::Integer),
+            ))
+        };
+
+        let command = Command::CompositeCommand(CompositeCommand::new(LogicalOperator::Or, vec![
+            single_command("movies.movie.runtime", "90"),
+            single_command("movies.movie.runtime", "120"),
+            Command::CompositeCommand(CompositeCommand::new(LogicalOperator::And, vec![
+                single_command("movies.movie.budget", "1"),
+                single_command("movies.movie.revenue", "2"),
+                single_command("movies.movie.vote_count", "3"),
+            ])),
+            single_command("movies.movie.runtime", "150"),
+        ]));
+
+        let where_query = create_where_query(&command, &PostgresDialect, false)?;
+
+        assert_eq!(
+            where_query.sql,
+            "WHERE ((\"movies\".\"movie\".\"runtime\" = $1::BIGINT) OR (\"movies\".\"movie\".\"runtime\" = $2::BIGINT) OR \
+            ((\"movies\".\"movie\".\"budget\" = $3::BIGINT) AND (\"movies\".\"movie\".\"revenue\" = $4::BIGINT) AND (\"movies\".\"movie\".\"vote_count\" = $5::BIGINT)) OR \
+            (\"movies\".\"movie\".\"runtime\" = $6::BIGINT))",
+        );
+        assert_eq!(where_query.parameters.len(), 6);
+
+        let empty_command = Command::CompositeCommand(CompositeCommand::new(LogicalOperator::And, vec![]));
+
+        assert_eq!(create_where_query(&empty_command, &PostgresDialect, false)?.sql, "WHERE (1 = 1)");
+
+        let empty_command = Command::CompositeCommand(CompositeCommand::new(LogicalOperator::Or, vec![]));
+
+        assert_eq!(create_where_query(&empty_command, &PostgresDialect, false)?.sql, "WHERE (1 = 0)");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_create_where_query_binds_values() -> Result<(), Error> {
+
+        let command = Command::CompositeCommand(CompositeCommand::new(LogicalOperator::And, vec![
+            Command::SingleCommand(SingleCommand::new(
+                "movies.person.person_name".to_string(),
+                Operator::EqualTo,
+                Value::new(r"Conan O'Brien \o/ --".into(), DataType::String),
+            )),
+            Command::SingleCommand(SingleCommand::new(
+                "movies.movie.popularity".to_string(),
+                Operator::GreaterThan,
+                Value::new("12.5".into(), DataType::Float),
+            )),
+        ]));
+
+        assert_eq!(
+            create_where_query(&command, &PostgresDialect, false)?,
+            Query::new(
+                "WHERE ((\"movies\".\"person\".\"person_name\" = $1::TEXT) AND (\"movies\".\"movie\".\"popularity\" > $2::DOUBLE PRECISION))".into(),
+                vec![
+                    QueryParameter::String(r"Conan O'Brien \o/ --".into()),
+                    QueryParameter::Float(12.5),
+                ],
+            )
+        );
+        assert_eq!(
+            create_where_query(&command, &MySQLDialect::for_server_version("8.0.36"), false)?.sql,
+            "WHERE ((`movies`.`person`.`person_name` = ?) AND (`movies`.`movie`.`popularity` > ?))",
+        );
+
+        let invalid_command = Command::SingleCommand(SingleCommand::new(
+            "movies.movie.runtime".to_string(),
+            Operator::EqualTo,
+            Value::new("1; DROP TABLE `movies`.`movie`".into(), DataType::Integer),
+        ));
+
+        assert!(create_where_query(&invalid_command, &PostgresDialect, false).is_err());
+
+        Ok(())
+    }
 
     #[test]
     fn test_command_to_query_simple_command() -> Result<(), Error> {
         let mut projection: Vec<String> = Vec::new();
-        projection.push("movies.movie.title::TEXT".to_string());
-        projection.push("movies.movie.runtime::TEXT".to_string());
+        projection.push("movies.movie.title".to_string());
+        projection.push("movies.movie.runtime".to_string());
 
         let simple_command = SingleCommand::new(
             "movies.movie.runtime".to_string(),
@@ -333,15 +1357,18 @@ mod tests {
         let fks: Vec<ForeignKey> = vec![];
         let ts = TableSearch::new(tables, fks);
 
-        let query = command_to_query(projection, &command, &ts)?;
+        let query = command_to_query(projection, &command, &ts, &PostgresDialect, &QueryOptions::default())?;
 
         assert_eq!(
             query,
-            format!(
-                "{}\n{}\n{}",
-                "SELECT movies.movie.title::TEXT, movies.movie.runtime::TEXT",
-                "FROM movies.movie",
-                "WHERE (movies.movie.runtime > 200);"
+            Query::new(
+                format!(
+                    "{}\n{}\n{}",
+                    "SELECT \"movies\".\"movie\".\"title\"::TEXT, \"movies\".\"movie\".\"runtime\"::TEXT",
+                    "FROM \"movies\".\"movie\"",
+                    "WHERE (\"movies\".\"movie\".\"runtime\" > $1::BIGINT);"
+                ),
+                vec![QueryParameter::Integer(200)],
             )
         );
 
@@ -351,8 +1378,8 @@ mod tests {
     #[test]
     fn test_command_to_query_attribute_as_value() -> Result<(), Error> {
         let mut projection: Vec<String> = Vec::new();
-        projection.push("movies.movie.title::TEXT".to_string());
-        projection.push("movies.person.person_name::TEXT".to_string());
+        projection.push("movies.movie.title".to_string());
+        projection.push("movies.person.person_name".to_string());
 
         let simple_command = SingleCommand::new(
             "movies.person.person_name".to_string(),
@@ -393,16 +1420,68 @@ mod tests {
 
         let ts = TableSearch::new(tables, fks);
 
-        let query = command_to_query(projection, &command, &ts)?;
+        let query = command_to_query(projection, &command, &ts, &PostgresDialect, &QueryOptions::default())?;
 
+        assert!(query.parameters.is_empty());
         assert_eq!(
-            query,
+            query.sql,
             format!(
                 "{}\n{}\n{}",
-                "SELECT movies.movie.title::TEXT, movies.person.person_name::TEXT",
-                "FROM movies.movie, movies.movie_cast, movies.person",
-                "WHERE (movies.movie.movie_id = movies.movie_cast.movie_id AND movies.movie_cast.person_id = movies.person.person_id) AND \
-                (movies.person.person_name = movies.movie_cast.character_name);"
+                "SELECT \"movies\".\"movie\".\"title\"::TEXT, \"movies\".\"person\".\"person_name\"::TEXT",
+                "FROM \"movies\".\"movie\"\n\
+                JOIN \"movies\".\"movie_cast\" ON \"movies\".\"movie\".\"movie_id\" = \"movies\".\"movie_cast\".\"movie_id\"\n\
+                JOIN \"movies\".\"person\" ON \"movies\".\"movie_cast\".\"person_id\" = \"movies\".\"person\".\"person_id\"",
+                "WHERE (\"movies\".\"person\".\"person_name\" = \"movies\".\"movie_cast\".\"character_name\");"
+            )
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_command_to_query_attribute_as_range_bound() -> Result<(), Error> {
+        let projection = vec!["movies.movie.title".to_string()];
+
+        let command = Command::SingleCommand(SingleCommand::new(
+            "movies.movie.runtime".to_string(),
+            Operator::Between,
+            Value::new_range(
+                Value::new("1".to_string(), DataType::Integer),
+                Value::new("movies.movie_cast.cast_order".to_string(), DataType::Attribute),
+            ),
+        ));
+
+        let tables: Vec<TableSearchInfo> = vec![TableSearchInfo {
+            schema: "movies".into(),
+            name: "movie".into(),
+        },
+        TableSearchInfo {
+            schema: "movies".into(),
+            name: "movie_cast".into(),
+        }];
+
+        let fks: Vec<ForeignKey> = vec![ForeignKey {
+            schema_name: "movies".into(),
+            table_name: "movie".into(),
+            attribute_name: "movie_id".into(),
+            schema_name_foreign: "movies".into(),
+            table_name_foreign: "movie_cast".into(),
+            attribute_name_foreign: "movie_id".into(),
+        }];
+
+        let ts = TableSearch::new(tables, fks);
+
+        let query = command_to_query(projection, &command, &ts, &PostgresDialect, &QueryOptions::default())?;
+
+        assert_eq!(query.parameters, vec![QueryParameter::Integer(1)]);
+        assert_eq!(
+            query.sql,
+            format!(
+                "{}\n{}\n{}",
+                "SELECT \"movies\".\"movie\".\"title\"::TEXT",
+                "FROM \"movies\".\"movie\"\n\
+                JOIN \"movies\".\"movie_cast\" ON \"movies\".\"movie\".\"movie_id\" = \"movies\".\"movie_cast\".\"movie_id\"",
+                "WHERE (\"movies\".\"movie\".\"runtime\" BETWEEN $1::BIGINT AND \"movies\".\"movie_cast\".\"cast_order\");"
             )
         );
 
@@ -412,10 +1491,10 @@ mod tests {
     #[test]
     fn test_intermediary_to_final_composite_command() -> Result<(), Error> {
         let mut projection: Vec<String> = Vec::new();
-        projection.push("movies.movie.title::TEXT".to_string());
-        projection.push("movies.movie.revenue::TEXT".to_string());
-        projection.push("movies.movie.runtime::TEXT".to_string());
-        projection.push("movies.movie.budget::TEXT".to_string());
+        projection.push("movies.movie.title".to_string());
+        projection.push("movies.movie.revenue".to_string());
+        projection.push("movies.movie.runtime".to_string());
+        projection.push("movies.movie.budget".to_string());
 
         let mut nested_commands: Vec<Command> = Vec::new();
         let mut nested_commands_2: Vec<Command> = Vec::new();
@@ -457,15 +1536,23 @@ mod tests {
         let fks: Vec<ForeignKey> = vec![];
         let ts = TableSearch::new(tables, fks);
 
-        let query = command_to_query(projection, &command, &ts)?;
+        let query = command_to_query(projection, &command, &ts, &PostgresDialect, &QueryOptions::default())?;
 
         assert_eq!(
-            query, 
+            query.sql, 
             format!("{}\n{}\n{}", 
-            "SELECT movies.movie.title::TEXT, movies.movie.revenue::TEXT, movies.movie.runtime::TEXT, movies.movie.budget::TEXT", 
-            "FROM movies.movie", 
-            "WHERE (((movies.movie.runtime > 200) OR (movies.movie.revenue > 1000000)) AND (movies.movie.budget > 1000000));"
+            "SELECT \"movies\".\"movie\".\"title\"::TEXT, \"movies\".\"movie\".\"revenue\"::TEXT, \"movies\".\"movie\".\"runtime\"::TEXT, \"movies\".\"movie\".\"budget\"::TEXT", 
+            "FROM \"movies\".\"movie\"", 
+            "WHERE (((\"movies\".\"movie\".\"runtime\" > $1::BIGINT) OR (\"movies\".\"movie\".\"revenue\" > $2::BIGINT)) AND (\"movies\".\"movie\".\"budget\" > $3::BIGINT));"
         ));
+        assert_eq!(
+            query.parameters,
+            vec![
+                QueryParameter::Integer(200),
+                QueryParameter::Integer(1000000),
+                QueryParameter::Integer(1000000),
+            ]
+        );
 
         Ok(())
     }
@@ -473,8 +1560,8 @@ mod tests {
     #[test]
     fn test_intermediary_to_final_composite_command_2() -> Result<(), Error> {
         let mut projection: Vec<String> = Vec::new();
-        projection.push("movies.movie.movie_id::TEXT".to_string());
-        projection.push("movies.movie.title::TEXT".to_string());
+        projection.push("movies.movie.movie_id".to_string());
+        projection.push("movies.movie.title".to_string());
 
         let simple_command = SingleCommand::new(
             "movies.country.country_name".to_string(),
@@ -514,16 +1601,247 @@ mod tests {
         }];
         let ts = TableSearch::new(tables, fks);
 
-        let query = command_to_query(projection, &command, &ts)?;
+        let query = command_to_query(projection.clone(), &command, &ts, &PostgresDialect, &QueryOptions::default())?;
 
-        assert_eq!(query, format!(
+        assert_eq!(query.sql, format!(
             "{}\n{}\n{}", 
-            "SELECT movies.movie.movie_id::TEXT, movies.movie.title::TEXT", 
-            "FROM movies.country, movies.movie, movies.production_country",
-            "WHERE (\
-            movies.country.country_id = movies.production_country.country_id AND \
-            movies.movie.movie_id = movies.production_country.movie_id) AND \
-            (movies.country.country_name = 'Brazil');"
+            "SELECT \"movies\".\"movie\".\"movie_id\"::TEXT, \"movies\".\"movie\".\"title\"::TEXT", 
+            "FROM \"movies\".\"movie\"\n\
+            JOIN \"movies\".\"production_country\" ON \"movies\".\"movie\".\"movie_id\" = \"movies\".\"production_country\".\"movie_id\"\n\
+            JOIN \"movies\".\"country\" ON \"movies\".\"production_country\".\"country_id\" = \"movies\".\"country\".\"country_id\"",
+            "WHERE (\"movies\".\"country\".\"country_name\" = $1::TEXT);"
+        ));
+        assert_eq!(query.parameters, vec![QueryParameter::String("Brazil".into())]);
+
+        let options = QueryOptions {
+            order_by: vec![OrderBy {
+                attribute: "movies.country.country_name".into(),
+                direction: Direction::Desc,
+                nulls: None,
+            }],
+            ..Default::default()
+        };
+        let command = Command::SingleCommand(SingleCommand::new(
+            "movies.movie.title".to_string(),
+            Operator::EqualTo,
+            Value::new("Central Station".into(), DataType::String),
+        ));
+
+        let query = command_to_query(projection.clone(), &command, &ts, &PostgresDialect, &options)?;
+
+        assert_eq!(query.sql, format!(
+            "{}\n{}\n{}\n{}", 
+            "SELECT \"movies\".\"movie\".\"movie_id\"::TEXT, \"movies\".\"movie\".\"title\"::TEXT", 
+            "FROM \"movies\".\"movie\"\n\
+            JOIN \"movies\".\"production_country\" ON \"movies\".\"movie\".\"movie_id\" = \"movies\".\"production_country\".\"movie_id\"\n\
+            JOIN \"movies\".\"country\" ON \"movies\".\"production_country\".\"country_id\" = \"movies\".\"country\".\"country_id\"",
+            "WHERE (\"movies\".\"movie\".\"title\" = $1::TEXT)",
+            "ORDER BY \"movies\".\"country\".\"country_name\" DESC;"
+        ));
+
+        let options = QueryOptions {
+            page: Some(Page { limit: 10, offset: 20 }),
+            ..options
+        };
+
+        let query = command_to_query(projection.clone(), &command, &ts, &PostgresDialect, &options)?;
+
+        assert_eq!(query.sql, format!(
+            "{}\n{}\n{}\n{}\n{}", 
+            "SELECT \"movies\".\"movie\".\"movie_id\"::TEXT, \"movies\".\"movie\".\"title\"::TEXT", 
+            "FROM \"movies\".\"movie\"\n\
+            JOIN \"movies\".\"production_country\" ON \"movies\".\"movie\".\"movie_id\" = \"movies\".\"production_country\".\"movie_id\"\n\
+            JOIN \"movies\".\"country\" ON \"movies\".\"production_country\".\"country_id\" = \"movies\".\"country\".\"country_id\"",
+            "WHERE (\"movies\".\"movie\".\"title\" = $1::TEXT)",
+            "ORDER BY \"movies\".\"country\".\"country_name\" DESC",
+            "LIMIT 10 OFFSET 20;"
+        ));
+
+        let cursor_options = QueryOptions {
+            cursor_attributes: vec!["movies.country.country_name".into()],
+            ..options.clone()
+        };
+
+        let query = command_to_query(projection.clone(), &command, &ts, &PostgresDialect, &cursor_options)?;
+
+        assert!(query.sql.starts_with(
+            "SELECT \"movies\".\"movie\".\"movie_id\"::TEXT, \"movies\".\"movie\".\"title\"::TEXT, \"movies\".\"country\".\"country_name\"::TEXT AS cursor_0\n"
+        ));
+
+        let aggregate_options = QueryOptions {
+            aggregates: vec![Aggregate {
+                function: AggregateFunction::CountDistinct,
+                attribute: Some("movies.movie.movie_id".into()),
+                alias: None,
+            }],
+            group_by: vec!["movies.country.country_name".into()],
+            order_by: vec![OrderBy {
+                attribute: "count_distinct_movie_id".into(),
+                direction: Direction::Desc,
+                nulls: None,
+            }],
+            ..Default::default()
+        };
+
+        let query = command_to_query(vec!["movies.country.country_name".into()], &command, &ts, &PostgresDialect, &aggregate_options)?;
+
+        assert_eq!(query.sql, format!(
+            "{}\n{}\n{}\n{}\n{}", 
+            "SELECT \"movies\".\"country\".\"country_name\"::TEXT, COUNT(DISTINCT \"movies\".\"movie\".\"movie_id\")::TEXT AS \"count_distinct_movie_id\"", 
+            "FROM \"movies\".\"country\"\n\
+            JOIN \"movies\".\"production_country\" ON \"movies\".\"country\".\"country_id\" = \"movies\".\"production_country\".\"country_id\"\n\
+            JOIN \"movies\".\"movie\" ON \"movies\".\"production_country\".\"movie_id\" = \"movies\".\"movie\".\"movie_id\"",
+            "WHERE (\"movies\".\"movie\".\"title\" = $1::TEXT)",
+            "GROUP BY \"movies\".\"country\".\"country_name\"",
+            "ORDER BY COUNT(DISTINCT \"movies\".\"movie\".\"movie_id\") DESC;"
+        ));
+
+        let having_options = QueryOptions {
+            having: Some(Command::SingleCommand(SingleCommand::new(
+                "count_distinct_movie_id".into(),
+                Operator::GreaterThan,
+                Value::new("50".into(), DataType::Integer),
+            ))),
+            order_by: vec![],
+            ..aggregate_options.clone()
+        };
+
+        let query = command_to_query(vec!["movies.country.country_name".into()], &command, &ts, &PostgresDialect, &having_options)?;
+
+        assert!(query.sql.ends_with(
+            "GROUP BY \"movies\".\"country\".\"country_name\"\n\
+            HAVING (COUNT(DISTINCT \"movies\".\"movie\".\"movie_id\") > $2::BIGINT);"
+        ));
+        assert_eq!(query.parameters, vec![
+            QueryParameter::String("Central Station".into()),
+            QueryParameter::Integer(50),
+        ]);
+
+        let aggregate_options = QueryOptions {
+            count: true,
+            ..aggregate_options
+        };
+
+        let query = command_to_query(vec!["movies.country.country_name".into()], &command, &ts, &PostgresDialect, &aggregate_options)?;
+
+        assert_eq!(query.sql, format!(
+            "{}\n{}\n{}\n{}\n{}", 
+            "SELECT COUNT(*)::TEXT AS total_count", 
+            "FROM (SELECT \"movies\".\"country\".\"country_name\"::TEXT, COUNT(DISTINCT \"movies\".\"movie\".\"movie_id\")::TEXT AS \"count_distinct_movie_id\"", 
+            "FROM \"movies\".\"country\"\n\
+            JOIN \"movies\".\"production_country\" ON \"movies\".\"country\".\"country_id\" = \"movies\".\"production_country\".\"country_id\"\n\
+            JOIN \"movies\".\"movie\" ON \"movies\".\"production_country\".\"movie_id\" = \"movies\".\"movie\".\"movie_id\"",
+            "WHERE (\"movies\".\"movie\".\"title\" = $1::TEXT)",
+            "GROUP BY \"movies\".\"country\".\"country_name\") AS results;"
+        ));
+
+        let options = QueryOptions {
+            count: true,
+            ..options
+        };
+
+        let query = command_to_query(projection, &command, &ts, &PostgresDialect, &options)?;
+
+        assert_eq!(query.sql, format!(
+            "{}\n{}\n{}", 
+            "SELECT COUNT(*)::TEXT AS total_count", 
+            "FROM \"movies\".\"movie\"\n\
+            JOIN \"movies\".\"production_country\" ON \"movies\".\"movie\".\"movie_id\" = \"movies\".\"production_country\".\"movie_id\"\n\
+            JOIN \"movies\".\"country\" ON \"movies\".\"production_country\".\"country_id\" = \"movies\".\"country\".\"country_id\"",
+            "WHERE (\"movies\".\"movie\".\"title\" = $1::TEXT);"
+        ));
+        assert_eq!(query.parameters, vec![QueryParameter::String("Central Station".into())]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_intermediary_to_final_with_semi_joins() -> Result<(), Error> {
+        let tables: Vec<TableSearchInfo> = ["movie", "production_country", "country"]
+            .iter()
+            .map(|name| TableSearchInfo::new("movies".into(), name.to_string()))
+            .collect();
+
+        let fks: Vec<ForeignKey> = vec![ForeignKey {
+            schema_name: "movies".into(),
+            table_name: "production_country".into(),
+            attribute_name: "movie_id".into(),
+            schema_name_foreign: "movies".into(),
+            table_name_foreign: "movie".into(),
+            attribute_name_foreign: "movie_id".into(),
+        }, ForeignKey {
+            schema_name: "movies".into(),
+            table_name: "production_country".into(),
+            attribute_name: "country_id".into(),
+            schema_name_foreign: "movies".into(),
+            table_name_foreign: "country".into(),
+            attribute_name_foreign: "country_id".into(),
+        }];
+        let ts = TableSearch::new(tables, fks);
+
+        let command = Command::SingleCommand(SingleCommand::new(
+            "movies.country.country_name".to_string(),
+            Operator::EqualTo,
+            Value::new("Brazil".into(), DataType::String),
+        ));
+        let options = QueryOptions {
+            semi_joins: true,
+            ..Default::default()
+        };
+
+        // a movie has many production countries
+        let projection = vec!["movies.movie.title".to_string()];
+        let query = command_to_query(projection.clone(), &command, &ts, &PostgresDialect, &options)?;
+
+        assert_eq!(query.sql, format!(
+            "{}\n{}\n{}", 
+            "SELECT \"movies\".\"movie\".\"title\"::TEXT", 
+            "FROM \"movies\".\"movie\"",
+            "WHERE EXISTS (SELECT 1\n\
+            FROM \"movies\".\"production_country\"\n\
+            JOIN \"movies\".\"country\" ON \"movies\".\"production_country\".\"country_id\" = \"movies\".\"country\".\"country_id\"\n\
+            WHERE \"movies\".\"movie\".\"movie_id\" = \"movies\".\"production_country\".\"movie_id\" AND (\"movies\".\"country\".\"country_name\" = $1::TEXT));"
+        ));
+        assert_eq!(query.parameters, vec![QueryParameter::String("Brazil".into())]);
+
+        let count_options = QueryOptions {
+            count: true,
+            ..options.clone()
+        };
+        let query = command_to_query(projection.clone(), &command, &ts, &PostgresDialect, &count_options)?;
+        assert!(query.sql.starts_with("SELECT COUNT(*)::TEXT AS total_count\nFROM \"movies\".\"movie\"\nWHERE EXISTS"));
+
+        // a production country has a single country
+        let query = command_to_query(vec!["movies.production_country.movie_id".to_string()], &command, &ts, &PostgresDialect, &options)?;
+
+        assert_eq!(query.sql, format!(
+            "{}\n{}\n{}", 
+            "SELECT \"movies\".\"production_country\".\"movie_id\"::TEXT", 
+            "FROM \"movies\".\"production_country\"\n\
+            JOIN \"movies\".\"country\" ON \"movies\".\"production_country\".\"country_id\" = \"movies\".\"country\".\"country_id\"",
+            "WHERE (\"movies\".\"country\".\"country_name\" = $1::TEXT);"
+        ));
+
+        let distinct_options = QueryOptions {
+            distinct: true,
+            order_by: vec![OrderBy {
+                attribute: "movies.movie.title".into(),
+                direction: Direction::Asc,
+                nulls: None,
+            }],
+            ..Default::default()
+        };
+        let query = command_to_query(projection, &command, &ts, &PostgresDialect, &distinct_options)?;
+
+        assert_eq!(query.sql, format!(
+            "{}\n{}\n{}\n{}\n{}", 
+            "SELECT \"movies\".\"movie\".\"title\"::TEXT", 
+            "FROM \"movies\".\"movie\"\n\
+            JOIN \"movies\".\"production_country\" ON \"movies\".\"movie\".\"movie_id\" = \"movies\".\"production_country\".\"movie_id\"\n\
+            JOIN \"movies\".\"country\" ON \"movies\".\"production_country\".\"country_id\" = \"movies\".\"country\".\"country_id\"",
+            "WHERE (\"movies\".\"country\".\"country_name\" = $1::TEXT)",
+            "GROUP BY \"movies\".\"movie\".\"title\"",
+            "ORDER BY \"movies\".\"movie\".\"title\" ASC;"
         ));
 
         Ok(())
@@ -532,8 +1850,8 @@ mod tests {
     #[test]
     fn test_intermediary_to_final_composite_command_3() -> Result<(), Error> {
         let mut projection: Vec<String> = Vec::new();
-        projection.push("movies.movie.movie_id::TEXT".to_string());
-        projection.push("movies.movie.title::TEXT".to_string());
+        projection.push("movies.movie.movie_id".to_string());
+        projection.push("movies.movie.title".to_string());
 
         let composite_command_1 = CompositeCommand::new(LogicalOperator::And, vec![
             Command::SingleCommand(SingleCommand::new(
@@ -615,26 +1933,33 @@ mod tests {
             }];
         let ts = TableSearch::new(tables, fks);
 
-        let query = command_to_query(projection, &command, &ts)?;
+        let query = command_to_query(projection, &command, &ts, &PostgresDialect, &QueryOptions::default())?;
 
-        assert_eq!(query, format!(
+        assert_eq!(query.sql, format!(
             "{}\n{}\n{}", 
-            "SELECT movies.movie.movie_id::TEXT, movies.movie.title::TEXT",
-            "FROM movies.country, movies.movie, movies.movie_company, movies.production_company, movies.production_country",
+            "SELECT \"movies\".\"movie\".\"movie_id\"::TEXT, \"movies\".\"movie\".\"title\"::TEXT",
+            "FROM \"movies\".\"movie\"\n\
+            JOIN \"movies\".\"movie_company\" ON \"movies\".\"movie\".\"movie_id\" = \"movies\".\"movie_company\".\"movie_id\"\n\
+            JOIN \"movies\".\"production_country\" ON \"movies\".\"movie\".\"movie_id\" = \"movies\".\"production_country\".\"movie_id\"\n\
+            JOIN \"movies\".\"country\" ON \"movies\".\"production_country\".\"country_id\" = \"movies\".\"country\".\"country_id\"\n\
+            JOIN \"movies\".\"production_company\" ON \"movies\".\"movie_company\".\"company_id\" = \"movies\".\"production_company\".\"company_id\"",
             "WHERE (\
-            movies.country.country_id = movies.production_country.country_id AND \
-            movies.movie.movie_id = movies.movie_company.movie_id AND \
-            movies.movie.movie_id = movies.production_country.movie_id AND \
-            movies.movie_company.company_id = movies.production_company.company_id) \
-            AND (\
             (\
-            (movies.production_company.company_name = 'Disney') \
+            (\"movies\".\"production_company\".\"company_name\" = $1::TEXT) \
             AND \
-            (movies.country.country_name = 'United States')\
+            (\"movies\".\"country\".\"country_name\" = $2::TEXT)\
             ) \
-            OR (movies.movie.budget <= 1000));"
+            OR (\"movies\".\"movie\".\"budget\" <= $3::BIGINT));"
             )
         );
+        assert_eq!(
+            query.parameters,
+            vec![
+                QueryParameter::String("Disney".into()),
+                QueryParameter::String("United States".into()),
+                QueryParameter::Integer(1000),
+            ]
+        );
 
         Ok(())
     }
@@ -27,11 +27,14 @@ pub struct TableSearch {
     table_identifier_to_node_index: HashMap<String, NodeIndex>,
     // undirected graph: nodes represent tables and edges represent foreign keys
     table_search_graph: Graph<String, String, Undirected>,
+    // foreign keys as (referencing attribute, referenced attribute), in the format schema.table.attribute
+    references: HashSet<(String, String)>,
 }
 
 impl TableSearch {
     pub fn new(tables: Vec<TableSearchInfo>, foreign_keys: Vec<ForeignKey>) -> Self {
         let mut table_search_graph = Graph::<String, String, Undirected>::new_undirected();
+        let mut references: HashSet<(String, String)> = HashSet::new();
         let table_identifier_to_node_index = tables
             .iter()
             .map(|t| {
@@ -56,6 +59,11 @@ impl TableSearch {
             let origin_table = format!("{}.{}", schema_name, table_name);
             let foreign_table = format!("{}.{}", schema_name_foreign, table_name_foreign);
 
+            references.insert((
+                format!("{}.{}", origin_table, attribute_name),
+                format!("{}.{}", foreign_table, attribute_name_foreign),
+            ));
+
             // the weight of the edge is the foreign key
             let weight = format!("{}:{}", attribute_name, attribute_name_foreign);
 
@@ -68,9 +76,17 @@ impl TableSearch {
         Self {
             table_identifier_to_node_index,
             table_search_graph,
+            references,
         }
     }
 
+    // Whether the attribute is a foreign key to the other one, so each row of its
+    // table matches at most one row of the other table.
+    pub fn references(&self, attribute: &str, other_attribute: &str) -> bool {
+        self.references
+            .contains(&(attribute.to_owned(), other_attribute.to_owned()))
+    }
+
     pub fn get_join_requirements(&self, atrs: &Vec<String>) -> (Vec<String>, Vec<String>) {
         println!("{:?}", atrs);
         let mut tables_needed: HashSet<String> = HashSet::from([]);
@@ -84,7 +100,7 @@ impl TableSearch {
                     let (new_tables, new_attrs) =
                         &self.get_attibute_pair_requirements(&atrs[i], &atrs[j]);
 
-                    if new_tables.len() > 0 {
+                    if !new_tables.is_empty() {
                         tables_uf.union(i, j);
                     }
 
@@ -123,11 +139,11 @@ impl TableSearch {
 
     fn get_attibute_pair_requirements(
         &self,
-        atr1: &String,
-        atr2: &String,
+        atr1: &str,
+        atr2: &str,
     ) -> (HashSet<String>, HashSet<String>) {
-        let (table_str1, _atr_str1) = &self.get_atr_info(&atr1);
-        let (table_str2, _atr_str2) = &self.get_atr_info(&atr2);
+        let (table_str1, _atr_str1) = &self.get_atr_info(atr1);
+        let (table_str2, _atr_str2) = &self.get_atr_info(atr2);
 
         let mut attributes_needed: HashSet<String> = HashSet::from([]);
 
@@ -148,13 +164,13 @@ impl TableSearch {
 
         }
 
-        let tables_needed_set: HashSet<String> = HashSet::from_iter(tables_needed.into_iter());
+        let tables_needed_set: HashSet<String> = HashSet::from_iter(tables_needed);
 
 
         (tables_needed_set, attributes_needed)
     }
 
-    fn get_atr_info(&self, atr: &String) -> (String, String) {
+    fn get_atr_info(&self, atr: &str) -> (String, String) {
         let words_vec: Vec<&str> = atr.split(".").collect();
 
         (
@@ -208,10 +224,9 @@ impl TableSearch {
         let path = astar(
             &self.table_search_graph,
             origin_index,               // start
-            |n| if destiny_index.is_none() {
-                    false
-                } else{
-                    n == destiny_index.unwrap()     // is_goal
+            |n| match destiny_index {
+                    None => false,
+                    Some(destiny) => n == destiny,  // is_goal
                 },
             |_| 1, // edge_cost
             |_| 0,           // estimate_cost
@@ -221,8 +236,8 @@ impl TableSearch {
         let mut ordered_edges = vec![];
         let mut ordered_nodes : Vec<NodeIndex> = vec![origin_index];
         
-        if !path.is_none() {
-            ordered_nodes = path.unwrap().1;
+        if let Some((_, nodes)) = path {
+            ordered_nodes = nodes;
         }
         
         let num_of_nodes = match destiny_index {
@@ -441,8 +456,8 @@ mod tests {
 
         let (nodes, edges) = ts.joinable_tables("A.B".to_string())?;
 
-        let expected_nodes = vec!["A.B".to_string(), "AA.BB".to_string(), "C.D".to_string()];
-        let expected_edges = vec!["e:f".to_string(), "g:h".to_string()];
+        let expected_nodes = ["A.B".to_string(), "AA.BB".to_string(), "C.D".to_string()];
+        let expected_edges = ["e:f".to_string(), "g:h".to_string()];
 
         assert!(
             nodes.iter().all(|node| expected_nodes.contains(node))
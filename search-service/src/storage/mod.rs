@@ -3,10 +3,13 @@ use crate::traits::Visitor;
 
 use crate::query_representation::intermediary::Command;
 
-use crate::query_representation::ultimate::command_to_query;
+use crate::query_representation::ultimate::dialect::SqlDialect;
+use crate::query_representation::ultimate::{command_to_query, Query, QueryOptions};
 
 use anyhow::Error;
 
+use std::sync::Arc;
+
 pub mod mysql;
 pub mod postgres;
 
@@ -14,17 +17,35 @@ pub mod postgres;
 pub struct DatabaseVisitor {
     //TableSearch struct with information on the db's tables
     pub table_search: TableSearch,
+    //SQL dialect of the database the query is built for, as given by SearchServiceStorage::get_dialect
+    pub dialect: Arc<dyn SqlDialect>,
+    //shape of the query chosen by the search, besides its filters
+    pub options: QueryOptions,
 }
 
 impl DatabaseVisitor {
-    pub fn new(table_search: TableSearch) -> Self {
-        Self { table_search }
+    pub fn new(
+        table_search: TableSearch,
+        dialect: Arc<dyn SqlDialect>,
+        options: QueryOptions,
+    ) -> Self {
+        Self {
+            table_search,
+            dialect,
+            options,
+        }
     }
 }
 
 impl Visitor for DatabaseVisitor {
-    fn visit_command(&self, projection: Vec<String>, command: &Command) -> Result<String, Error> {
-        let query = command_to_query(projection, command, &self.table_search)?;
+    fn visit_command(&self, projection: Vec<String>, command: &Command) -> Result<Query, Error> {
+        let query = command_to_query(
+            projection,
+            command,
+            &self.table_search,
+            self.dialect.as_ref(),
+            &self.options,
+        )?;
 
         Ok(query)
     }
@@ -43,6 +64,8 @@ mod tests {
     use crate::relational::entities::ForeignKey;
     use crate::relational::table_search::entities::TableSearchInfo;
     use crate::relational::table_search::TableSearch;
+    use crate::query_representation::ultimate::dialect::PostgresDialect;
+    use crate::query_representation::ultimate::QueryOptions;
     use crate::storage::DatabaseVisitor;
     use crate::traits::Component;
 
@@ -61,26 +84,30 @@ mod tests {
         let fks: Vec<ForeignKey> = vec![];
 
         let table_search = TableSearch::new(tables, fks);
-        let postgres_visitor = DatabaseVisitor::new(table_search);
+        let postgres_visitor = DatabaseVisitor::new(
+            table_search,
+            Arc::new(PostgresDialect),
+            QueryOptions::default(),
+        );
 
         let sc_return = Command::SingleCommand(simple_command).accept(
             vec![
-                "movies.movie.runtime::TEXT".to_string(),
-                "movies.movie.revenue::TEXT".to_string(),
+                "movies.movie.runtime".to_string(),
+                "movies.movie.revenue".to_string(),
             ],
             Arc::new(postgres_visitor.clone()),
         )?;
 
         let cc_return = Command::CompositeCommand(composite_command).accept(
             vec![
-                "movies.movie.runtime::TEXT".to_string(),
-                "movies.movie.revenue::TEXT".to_string(),
+                "movies.movie.runtime".to_string(),
+                "movies.movie.revenue".to_string(),
             ],
             Arc::new(postgres_visitor),
         )?;
 
-        assert_eq!(sc_return, "SELECT movies.movie.runtime::TEXT, movies.movie.revenue::TEXT\nFROM movies.movie\nWHERE (movies.movie.runtime > 200);".to_string());
-        assert_eq!(cc_return, "SELECT movies.movie.runtime::TEXT, movies.movie.revenue::TEXT\nFROM movies.movie\nWHERE ((movies.movie.runtime > 200) AND (movies.movie.revenue > 1000000));".to_string());
+        assert_eq!(sc_return.sql, "SELECT \"movies\".\"movie\".\"runtime\"::TEXT, \"movies\".\"movie\".\"revenue\"::TEXT\nFROM \"movies\".\"movie\"\nWHERE (\"movies\".\"movie\".\"runtime\" > $1::BIGINT);".to_string());
+        assert_eq!(cc_return.sql, "SELECT \"movies\".\"movie\".\"runtime\"::TEXT, \"movies\".\"movie\".\"revenue\"::TEXT\nFROM \"movies\".\"movie\"\nWHERE ((\"movies\".\"movie\".\"runtime\" > $1::BIGINT) AND (\"movies\".\"movie\".\"revenue\" > $2::BIGINT));".to_string());
 
         Ok(())
     }
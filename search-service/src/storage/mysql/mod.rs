@@ -1,10 +1,13 @@
-use anyhow::{Error, Ok, Result};
+use anyhow::{anyhow, Error, Ok, Result};
 use async_trait::async_trait;
 use mysql::prelude::Queryable;
-use mysql::{from_row, params, OptsBuilder, Pool, PooledConn};
+use mysql::{from_row, params, OptsBuilder, Params, Pool, PooledConn};
+use std::sync::Arc;
 use std::time::Duration;
 
 use crate::query_representation::intermediary::single_command::DataType;
+use crate::query_representation::ultimate::dialect::{MySQLDialect, SqlDialect};
+use crate::query_representation::ultimate::{Query, QueryParameter};
 use crate::relational::entities::{Attribute, DbSchema, ForeignKey, PrimaryKey, Table};
 use crate::traits::SearchServiceStorage;
 
@@ -43,7 +46,7 @@ impl MySQLConfig {
             user: mysql_user,
             password: mysql_pass,
             dbname: mysql_db,
-            allowed_schemas: allowed_schemas,
+            allowed_schemas,
         }
     }
     pub fn from_env() -> Self {
@@ -62,7 +65,7 @@ impl MySQLConfig {
             user: std::env::var("DB_USER").unwrap_or_else(|_| "searchservice".to_string()),
             password: std::env::var("DB_PASS").unwrap_or_else(|_| "searchservice".to_string()),
             dbname: std::env::var("DB_NAME").unwrap_or_else(|_| "searchservice".to_string()),
-            allowed_schemas: allowed_schemas,
+            allowed_schemas,
         }
     }
 }
@@ -70,6 +73,7 @@ impl MySQLConfig {
 pub struct MySQLStorage {
     pub pool: Pool,
     pub allowed_schemas: Vec<String>,
+    pub dialect: MySQLDialect,
 }
 
 impl MySQLStorage {
@@ -89,12 +93,26 @@ impl MySQLStorage {
 
         println!("Allowed Schemas (MySQL): {:?}", allowed_schemas);
 
+        // the collations used to compare text depend on the server version, an unknown
+        // version only gets the ones every server has
+        let version = Self::get_version(&pool).unwrap_or_default();
+
+        println!("MySQL version: {}", version);
+
         Ok(Self {
             pool,
             allowed_schemas,
+            dialect: MySQLDialect::for_server_version(&version),
         })
     }
 
+    fn get_version(pool: &Pool) -> Result<String> {
+        let mut client = pool.get_conn()?;
+        let version: Option<String> = client.query_first(queries::GET_VERSION)?;
+
+        version.ok_or_else(|| anyhow!("The server didn't tell its version"))
+    }
+
     fn get_client(&self) -> Result<PooledConn, Error> {
         let client = self.pool.get_conn()?;
 
@@ -105,11 +123,11 @@ impl MySQLStorage {
         let allowed_schemas: &Vec<String> = &self.allowed_schemas;
 
         let tables = self
-            .get_db_tables(&allowed_schemas)
+            .get_db_tables(allowed_schemas)
             .await
             .expect("Error retireving Database Tables");
         let foreign_keys = self
-            .get_db_foreign_keys(&allowed_schemas)
+            .get_db_foreign_keys(allowed_schemas)
             .await
             .expect("Error retireving Database Foreign Keys");
 
@@ -117,12 +135,12 @@ impl MySQLStorage {
         Ok(db_schema)
     }
 
-    async fn get_db_tables(&self, allowed_schemas: &Vec<String>) -> Result<Vec<Table>, Error> {
+    async fn get_db_tables(&self, allowed_schemas: &[String]) -> Result<Vec<Table>, Error> {
         let mut client = self.get_client()?;
 
         let mut table_vec: Vec<Table> = Vec::new();
 
-        let params = vec_to_mysql_list(&allowed_schemas)?;
+        let params = vec_to_mysql_list(allowed_schemas)?;
 
         let query_str: String = queries::GET_TABLES.replace(":allowed_schemas", params.as_str());
 
@@ -207,13 +225,13 @@ impl MySQLStorage {
 
     async fn get_db_foreign_keys(
         &self,
-        allowed_schemas: &Vec<String>,
+        allowed_schemas: &[String],
     ) -> Result<Vec<ForeignKey>, Error> {
         let mut client = self.get_client()?;
 
         let mut foreign_keys_vec: Vec<ForeignKey> = Vec::new();
 
-        let params = vec_to_mysql_list(&allowed_schemas)?;
+        let params = vec_to_mysql_list(allowed_schemas)?;
 
         let query_str: String =
             queries::GET_FOREIGN_KEYS.replace(":allowed_schemas", params.as_str());
@@ -251,11 +269,11 @@ impl SearchServiceStorage for MySQLStorage {
         let allowed_schemas: &Vec<String> = &self.allowed_schemas;
 
         let tables = self
-            .get_db_tables(&allowed_schemas)
+            .get_db_tables(allowed_schemas)
             .await
             .expect("Error retireving Database Tables");
         let foreign_keys = self
-            .get_db_foreign_keys(&allowed_schemas)
+            .get_db_foreign_keys(allowed_schemas)
             .await
             .expect("Error retireving Database Foreign Keys");
 
@@ -263,10 +281,22 @@ impl SearchServiceStorage for MySQLStorage {
         Ok(db_schema)
     }
 
-    async fn execute(&self, query: String) -> Result<Vec<serde_json::Value>, Error> {
+    async fn execute(&self, query: Query) -> Result<Vec<serde_json::Value>, Error> {
         let mut conn = self.get_client()?;
 
-        let rows = conn.query_iter(query)?;
+        let parameters: Vec<mysql::Value> = query
+            .parameters
+            .into_iter()
+            .map(|parameter| match parameter {
+                QueryParameter::Integer(integer) => mysql::Value::from(integer),
+                QueryParameter::Float(float) => mysql::Value::from(float),
+                QueryParameter::String(string) | QueryParameter::Date(string) => {
+                    mysql::Value::from(string)
+                }
+            })
+            .collect();
+
+        let rows = conn.exec_iter(query.sql, Params::from(parameters))?;
 
         Ok(rows
             .into_iter()
@@ -277,36 +307,41 @@ impl SearchServiceStorage for MySQLStorage {
     fn translate_native_type(&self, mysql_type: &str) -> Result<DataType,Error> {
 
         let data_type = match mysql_type {
-            "int" | "bigint" => {
+            "tinyint" | "smallint" | "mediumint" | "int" | "bigint" => {
                 DataType::Integer
             },
-            "varchar" => {
+            "varchar" | "char" | "tinytext" | "text" | "mediumtext" | "longtext" => {
                 DataType::String
             },
-            "decimal" => {
+            "decimal" | "float" | "double" => {
                 DataType::Float
             },
             "date" => {
                 DataType::Date
             }
-            _ => panic!("Unknown MySQL native type: {}",mysql_type)
+            _ => return Err(anyhow!("Unknown MySQL native type: {}",mysql_type))
         };
 
         Ok(data_type)
 
     }
 
-    fn get_database(&self) -> &str {
-        "mysql"
+    fn get_dialect(&self) -> Arc<dyn SqlDialect> {
+        Arc::new(self.dialect.clone())
+    }
+
+    // every server has a collation ignoring both case and accents
+    fn supports_unaccent(&self) -> bool {
+        true
     }
 }
 
-fn vec_to_mysql_list(v: &Vec<String>) -> Result<String> {
+fn vec_to_mysql_list(v: &[String]) -> Result<String> {
     let mut params = "".to_string();
     for (idx, item) in v.iter().enumerate() {
-        params.push_str("'");
+        params.push('\'');
         params.push_str(item);
-        params.push_str("'");
+        params.push('\'');
         if idx != v.len() - 1 {
             params.push_str(", ");
         }
@@ -33,4 +33,6 @@ pub const GET_FOREIGN_KEYS: &str = "
 			WHERE tc.constraint_type= 'FOREIGN KEY'
 			AND tc.table_schema IN ( :allowed_schemas )
 			AND kcu.referenced_table_schema IN ( :allowed_schemas );
-";
\ No newline at end of file
+";
+
+pub const GET_VERSION: &str = "SELECT VERSION();";
@@ -1,4 +1,5 @@
 #[cfg(test)]
+#[allow(clippy::module_inception)]
 mod tests {
     use mysql::PooledConn;
 
@@ -10,6 +11,8 @@ mod tests {
 
     use crate::traits::SearchServiceStorage;
 
+    use crate::query_representation::ultimate::{Query, QueryParameter};
+
     use crate::query_representation::intermediary::single_command::DataType;
 
     async fn setup_storage() -> MySQLStorage {
@@ -29,8 +32,8 @@ mod tests {
 
     async fn setup_client() -> PooledConn {
         let storage = setup_storage().await;
-        let client = storage.get_client().expect("Error getting MySQL client");
-        client
+        
+        storage.get_client().expect("Error getting MySQL client")
     }
 
     #[tokio::test]
@@ -133,8 +136,8 @@ mod tests {
         let storage = setup_storage().await;
         let schema_info = storage.get_db_schema_info().await?;
 
-        assert!(schema_info.tables.len() > 0);
-        assert!(schema_info.foreign_keys.len() > 0);
+        assert!(!schema_info.tables.is_empty());
+        assert!(!schema_info.foreign_keys.is_empty());
 
         Ok(())
     }
@@ -143,12 +146,12 @@ mod tests {
     async fn test_execute() -> Result<(), Error> {
         let storage = setup_storage().await;
 
-        let json = storage.execute(
+        let json = storage.execute(Query::new(
             "SELECT movies.movie_cast.character_name,movies.person.person_name \
             FROM movies.movie_cast, movies.person \
             WHERE movies.movie_cast.person_id = movies.person.person_id \
             ORDER BY movies.person.person_name ASC \
-            LIMIT 4;".to_string()).await?;
+            LIMIT 4;".to_string(), vec![])).await?;
         assert_eq!(json.len(),4);
         assert_eq!(json[0]["character_name"],"El Chiquis".to_string());
         assert_eq!(json[3]["person_name"],"'Snub' Pollard".to_string());
@@ -156,25 +159,57 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_execute_with_parameters() -> Result<(), Error> {
+        let storage = setup_storage().await;
+
+        let json = storage.execute(Query::new(
+            "SELECT movies.movie.title, movies.movie.runtime \
+            FROM movies.movie \
+            WHERE movies.movie.title = ? AND movies.movie.runtime > ? \
+            AND movies.movie.popularity > ? AND movies.movie.release_date = ?;".to_string(),
+            vec![
+                QueryParameter::String("Ocean's Eleven".into()),
+                QueryParameter::Integer(100),
+                QueryParameter::Float(1.5),
+                QueryParameter::Date("2001-12-07".into()),
+            ])).await?;
+
+        assert_eq!(json.len(),1);
+        assert_eq!(json[0]["title"],"Ocean's Eleven".to_string());
+        assert_eq!(json[0]["runtime"],"116".to_string());
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_translate_native_type() -> Result<(), Error> {
 
         let storage = setup_storage().await;
 
-        let mut native_type = "int".into();
+        let mut native_type = "int";
         assert_eq!(storage.translate_native_type(native_type)?,DataType::Integer);
 
-        native_type = "varchar".into();
+        native_type = "varchar";
         assert_eq!(storage.translate_native_type(native_type)?,DataType::String);
 
-        native_type = "decimal".into();
+        native_type = "decimal";
         assert_eq!(storage.translate_native_type(native_type)?,DataType::Float);
 
-        native_type = "bigint".into();
+        native_type = "bigint";
         assert_eq!(storage.translate_native_type(native_type)?,DataType::Integer);
 
-        native_type = "date".into();
+        native_type = "date";
         assert_eq!(storage.translate_native_type(native_type)?,DataType::Date);
+
+        native_type = "text";
+        assert_eq!(storage.translate_native_type(native_type)?,DataType::String);
+
+        native_type = "tinyint";
+        assert_eq!(storage.translate_native_type(native_type)?,DataType::Integer);
+
+        native_type = "json";
+        assert!(storage.translate_native_type(native_type).is_err());
         Ok(())
     }
 
@@ -4,11 +4,40 @@ use mysql::Row;
 pub fn row_to_json(row: Row) -> anyhow::Result<Value> {
     let mut object: Map<String, Value> = Map::new();
 
-    for (idx,column) in row.clone().columns().into_iter().enumerate(){
+    for (idx,column) in row.clone().columns().iter().enumerate(){
         let field_name = column.name_str();
-        let field_value: String = row.clone().get(idx).expect("Error getting row element");
-        object.insert(field_name.to_string(), serde_json::to_value(field_value)?);
+        let field_value = row.as_ref(idx).expect("Error getting row element");
+        object.insert(field_name.to_string(), mysql_value_to_json(field_value));
     }
 
     Ok(Value::Object(object))
 }
+
+// Prepared statements return typed values, they are written as text
+// just like the values of Postgres projections.
+fn mysql_value_to_json(value: &mysql::Value) -> Value {
+    let text = match value {
+        mysql::Value::NULL => return Value::Null,
+        mysql::Value::Bytes(bytes) => String::from_utf8_lossy(bytes).to_string(),
+        mysql::Value::Int(integer) => integer.to_string(),
+        mysql::Value::UInt(integer) => integer.to_string(),
+        mysql::Value::Float(float) => float.to_string(),
+        mysql::Value::Double(float) => float.to_string(),
+        mysql::Value::Date(year, month, day, 0, 0, 0, 0) => {
+            format!("{:04}-{:02}-{:02}", year, month, day)
+        }
+        mysql::Value::Date(year, month, day, hour, minutes, seconds, _) => format!(
+            "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+            year, month, day, hour, minutes, seconds
+        ),
+        mysql::Value::Time(negative, days, hours, minutes, seconds, _) => format!(
+            "{}{:02}:{:02}:{:02}",
+            if *negative { "-" } else { "" },
+            *days * 24 + *hours as u32,
+            minutes,
+            seconds
+        ),
+    };
+
+    Value::String(text)
+}
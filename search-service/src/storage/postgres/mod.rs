@@ -1,14 +1,19 @@
-use anyhow::{Error, Ok, Result};
+use anyhow::{anyhow, Error, Ok, Result};
 use async_trait::async_trait;
 use deadpool_postgres::{Manager, ManagerConfig, Object, Pool, RecyclingMethod};
+use tokio_postgres::types::ToSql;
 use tokio_postgres::NoTls;
 
+use std::sync::Arc;
+
 pub mod queries;
 #[cfg(test)]
 pub mod tests;
 pub mod utils;
 
 use crate::query_representation::intermediary::single_command::DataType;
+use crate::query_representation::ultimate::dialect::{PostgresDialect, SqlDialect};
+use crate::query_representation::ultimate::{Query, QueryParameter};
 use crate::relational::entities::{Attribute, DbSchema, ForeignKey, PrimaryKey, Table};
 use crate::relational::table_search::entities::TableSearchInfo;
 use crate::relational::table_search::TableSearch;
@@ -44,7 +49,7 @@ impl PostgresConfig {
             user: postgres_user,
             password: postgres_pass,
             dbname: postgres_db,
-            allowed_schemas: allowed_schemas,
+            allowed_schemas,
         }
     }
 
@@ -64,7 +69,7 @@ impl PostgresConfig {
             user: std::env::var("DB_USER").unwrap_or_else(|_| "search-service".to_string()),
             password: std::env::var("DB_PASS").unwrap_or_else(|_| "search-service".to_string()),
             dbname: std::env::var("DB_NAME").unwrap_or_else(|_| "search-service".to_string()),
-            allowed_schemas: allowed_schemas,
+            allowed_schemas,
         }
     }
 }
@@ -72,6 +77,7 @@ impl PostgresConfig {
 pub struct PostgresStorage {
     pub pool: Pool,
     pub allowed_schemas: Vec<String>,
+    pub unaccent: bool,
 }
 
 impl PostgresStorage {
@@ -99,12 +105,25 @@ impl PostgresStorage {
 
         println!("Allowed Schemas: {:?}", allowed_schemas);
 
+        // accent insensitive comparisons are only offered when the extension is installed
+        let unaccent = Self::has_unaccent(&pool).await.unwrap_or(false);
+
+        println!("Unaccent available: {}", unaccent);
+
         Ok(Self {
             pool,
             allowed_schemas,
+            unaccent,
         })
     }
 
+    async fn has_unaccent(pool: &Pool) -> Result<bool> {
+        let client = pool.get().await?;
+        let row = client.query_one(queries::HAS_UNACCENT, &[]).await?;
+
+        Ok(row.try_get("has_unaccent")?)
+    }
+
     async fn get_client(&self) -> Result<Object> {
         let client = self.pool.get().await?;
 
@@ -119,11 +138,11 @@ impl PostgresStorage {
             .await
             .expect("Unable to retrieve Postgres Client");
         let tables = self
-            .get_db_tables(&this_client, &allowed_schemas)
+            .get_db_tables(&this_client, allowed_schemas)
             .await
             .expect("Error retireving Database Tables");
         let foreign_keys = self
-            .get_db_foreign_keys(&this_client, &allowed_schemas)
+            .get_db_foreign_keys(&this_client, allowed_schemas)
             .await
             .expect("Error retireving Database Foreign Keys");
 
@@ -156,12 +175,12 @@ impl PostgresStorage {
             let table_name: String = tables_row.get("table_name");
 
             let attributes_vec: Vec<Attribute> = self
-                .get_table_attributes(&table_schema, &table_name, &client)
+                .get_table_attributes(&table_schema, &table_name, client)
                 .await
                 .expect("Error retrieving attributes");
 
             let primary_keys_vec: Vec<PrimaryKey> = self
-                .get_table_primary_keys(&table_schema, &table_name, &client)
+                .get_table_primary_keys(&table_schema, &table_name, client)
                 .await
                 .expect("Error retrieving primary keys");
 
@@ -264,11 +283,11 @@ impl SearchServiceStorage for PostgresStorage {
             .await
             .expect("Unable to retrieve Postgres Client");
         let tables = self
-            .get_db_tables(&this_client, &allowed_schemas)
+            .get_db_tables(&this_client, allowed_schemas)
             .await
             .expect("Error retireving Database Tables");
         let foreign_keys = self
-            .get_db_foreign_keys(&this_client, &allowed_schemas)
+            .get_db_foreign_keys(&this_client, allowed_schemas)
             .await
             .expect("Error retireving Database Foreign Keys");
 
@@ -276,11 +295,28 @@ impl SearchServiceStorage for PostgresStorage {
         Ok(db_schema)
     }
 
-    async fn execute(&self, query: String) -> Result<Vec<serde_json::Value>, Error> {
+    async fn execute(&self, query: Query) -> Result<Vec<serde_json::Value>, Error> {
         let conn = self.get_client().await?;
-        let stmt = conn.prepare_cached(&query).await?;
+        let stmt = conn.prepare_cached(&query.sql).await?;
 
-        let rows = conn.query(&stmt, &[]).await?;
+        // placeholders are cast in the query, dates are sent as text
+        let parameters: Vec<Box<dyn ToSql + Sync + Send>> = query
+            .parameters
+            .into_iter()
+            .map(|parameter| -> Box<dyn ToSql + Sync + Send> {
+                match parameter {
+                    QueryParameter::Integer(integer) => Box::new(integer),
+                    QueryParameter::Float(float) => Box::new(float),
+                    QueryParameter::String(string) | QueryParameter::Date(string) => Box::new(string),
+                }
+            })
+            .collect();
+        let parameters: Vec<&(dyn ToSql + Sync)> = parameters
+            .iter()
+            .map(|parameter| parameter.as_ref() as &(dyn ToSql + Sync))
+            .collect();
+
+        let rows = conn.query(&stmt, &parameters).await?;
 
         Ok(rows
             .into_iter()
@@ -290,26 +326,30 @@ impl SearchServiceStorage for PostgresStorage {
 
     fn translate_native_type(&self, postgres_type: &str) -> Result<DataType,Error> {
         let data_type = match postgres_type {
-            "integer" | "bigint" => {
+            "smallint" | "integer" | "bigint" => {
                 DataType::Integer
             },
-            "character varying" => {
+            "character varying" | "character" | "text" => {
                 DataType::String
             },
-            "numeric" => {
+            "numeric" | "real" | "double precision" => {
                 DataType::Float
             },
             "date" => {
                 DataType::Date
             },
-            _ => panic!("Unknown Postgres native type: {}",postgres_type)
+            _ => return Err(anyhow!("Unknown Postgres native type: {}",postgres_type))
         };
 
         Ok(data_type)
     }
 
-    fn get_database(&self) -> &str {
-        "postgres"
+    fn get_dialect(&self) -> Arc<dyn SqlDialect> {
+        Arc::new(PostgresDialect)
+    }
+
+    fn supports_unaccent(&self) -> bool {
+        self.unaccent
     }
 
 
@@ -37,4 +37,9 @@ pub const GET_FOREIGN_KEYS: &str = "
             JOIN information_schema.constraint_column_usage AS ccu
                 ON ccu.constraint_name = tc.constraint_name
             WHERE tc.constraint_type = 'FOREIGN KEY' AND
-            tc.table_schema = any($1) AND ccu.table_schema = any($1);";
\ No newline at end of file
+            tc.table_schema = any($1) AND ccu.table_schema = any($1);";
+
+pub const HAS_UNACCENT: &str = "
+			SELECT EXISTS (
+				SELECT 1 FROM pg_extension WHERE extname = 'unaccent'
+			) AS has_unaccent;";
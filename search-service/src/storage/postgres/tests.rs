@@ -1,4 +1,5 @@
 #[cfg(test)]
+#[allow(clippy::module_inception)]
 mod tests {
     use anyhow::Error;
 
@@ -8,6 +9,8 @@ mod tests {
 
     use crate::traits::SearchServiceStorage;
 
+    use crate::query_representation::ultimate::{Query, QueryParameter};
+
     use crate::query_representation::intermediary::single_command::DataType;
 
     async fn setup_storage() -> PostgresStorage {
@@ -141,8 +144,8 @@ mod tests {
 
         let schema_info = storage.get_db_schema_info().await?;
 
-        assert!(schema_info.tables.len() > 0);
-        assert!(schema_info.foreign_keys.len() > 0);
+        assert!(!schema_info.tables.is_empty());
+        assert!(!schema_info.foreign_keys.is_empty());
 
         Ok(())
     }
@@ -151,11 +154,11 @@ mod tests {
     async fn test_execute() -> Result<(), Error> {
         let storage = setup_storage().await;
 
-        let json = storage.execute(
+        let json = storage.execute(Query::new(
             "SELECT movies.movie.title::TEXT, movies.movie.revenue::TEXT \
             FROM movies.movie \
             ORDER BY movies.movie.title ASC \
-            LIMIT 2;".to_string()).await?;
+            LIMIT 2;".to_string(), vec![])).await?;
 
         assert_eq!(json.len(),2);
         assert_eq!(json[0]["revenue"],"0".to_string());
@@ -164,25 +167,56 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_execute_with_parameters() -> Result<(), Error> {
+        let storage = setup_storage().await;
+
+        let json = storage.execute(Query::new(
+            "SELECT movies.movie.title::TEXT \
+            FROM movies.movie \
+            WHERE movies.movie.title = $1::TEXT AND movies.movie.runtime > $2::BIGINT \
+            AND movies.movie.popularity > $3::DOUBLE PRECISION AND movies.movie.release_date = $4::TEXT::DATE;".to_string(),
+            vec![
+                QueryParameter::String("Ocean's Eleven".into()),
+                QueryParameter::Integer(100),
+                QueryParameter::Float(1.5),
+                QueryParameter::Date("2001-12-07".into()),
+            ])).await?;
+
+        assert_eq!(json.len(),1);
+        assert_eq!(json[0]["title"],"Ocean's Eleven".to_string());
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_translate_native_type() -> Result<(), Error> {
 
         let storage = setup_storage().await;
 
-        let mut native_type = "integer".into();
+        let mut native_type = "integer";
         assert_eq!(storage.translate_native_type(native_type)?,DataType::Integer);
 
-        native_type = "character varying".into();
+        native_type = "character varying";
         assert_eq!(storage.translate_native_type(native_type)?,DataType::String);
 
-        native_type = "numeric".into();
+        native_type = "numeric";
         assert_eq!(storage.translate_native_type(native_type)?,DataType::Float);
 
-        native_type = "bigint".into();
+        native_type = "bigint";
         assert_eq!(storage.translate_native_type(native_type)?,DataType::Integer);
 
-        native_type = "date".into();
+        native_type = "date";
         assert_eq!(storage.translate_native_type(native_type)?,DataType::Date);
+
+        native_type = "text";
+        assert_eq!(storage.translate_native_type(native_type)?,DataType::String);
+
+        native_type = "smallint";
+        assert_eq!(storage.translate_native_type(native_type)?,DataType::Integer);
+
+        native_type = "boolean";
+        assert!(storage.translate_native_type(native_type).is_err());
         Ok(())
     }
 
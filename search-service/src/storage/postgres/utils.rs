@@ -6,7 +6,8 @@ pub fn row_to_json(row: Row) -> anyhow::Result<Value> {
 
     for column in row.columns().iter() {
         let field_name = column.name();
-        let field_value: String = row.try_get(field_name)?;
+        // columns of tables joined with LEFT JOIN may be NULL
+        let field_value: Option<String> = row.try_get(field_name)?;
         object.insert(field_name.to_string(), serde_json::to_value(field_value)?);
     }
 
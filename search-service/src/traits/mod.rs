@@ -2,17 +2,19 @@ use std::sync::Arc;
 
 use crate::query_representation::intermediary::Command;
 use crate::query_representation::intermediary::single_command::DataType;
+use crate::query_representation::ultimate::dialect::SqlDialect;
+use crate::query_representation::ultimate::Query;
 use crate::relational::entities::DbSchema;
 use anyhow::Error;
 
 use async_trait::async_trait;
 
 pub trait Component {
-    fn accept(&self, projection: Vec<String>, v: Arc<dyn Visitor>) -> Result<String, Error>;
+    fn accept(&self, projection: Vec<String>, v: Arc<dyn Visitor>) -> Result<Query, Error>;
 }
 
 pub trait Visitor {
-    fn visit_command(&self, projection: Vec<String>, command: &Command) -> Result<String, Error>;
+    fn visit_command(&self, projection: Vec<String>, command: &Command) -> Result<Query, Error>;
 }
 
 pub trait Expression {
@@ -22,7 +24,8 @@ pub trait Expression {
 #[async_trait]
 pub trait SearchServiceStorage: Sync + Send {
     async fn get_db_schema_info(&self) -> Result<DbSchema, Error>;
-    async fn execute(&self, query: String) -> Result<Vec<serde_json::Value>, Error>;
-    fn get_database(&self) -> &str;
+    async fn execute(&self, query: Query) -> Result<Vec<serde_json::Value>, Error>;
+    fn get_dialect(&self) -> Arc<dyn SqlDialect>;
+    fn supports_unaccent(&self) -> bool;
     fn translate_native_type(&self, native_type: &str) -> Result<DataType,Error>;
 }